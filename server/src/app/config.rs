@@ -0,0 +1,132 @@
+use std::{
+    io,
+    net::{TcpListener, ToSocketAddrs},
+    time::Duration,
+};
+
+use thiserror::Error;
+
+use crate::{models::encoding::EncodingType, thread_pool::PoolCreationError};
+
+use super::{App, limits::Limits, timeouts::Timeouts};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerConfig {
+    // Threads serving connections; each handles one connection at a time.
+    pub workers: usize,
+    pub worker_name: String,
+    // Stack size of each worker thread, or the platform default.
+    pub worker_stack_size: Option<usize>,
+    // How long an idle connection is kept open waiting for the next request.
+    // Zero closes every connection after its first response.
+    pub keep_alive: Duration,
+    // Number of requests served on one connection before it is closed.
+    pub max_requests_per_connection: usize,
+    pub limits: Limits,
+    pub timeouts: Timeouts,
+    pub encodings: Vec<EncodingType>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            workers: 5,
+            worker_name: String::from("http-worker"),
+            worker_stack_size: None,
+            keep_alive: Duration::from_secs(5),
+            max_requests_per_connection: 100,
+            limits: Limits::default(),
+            timeouts: Timeouts::default(),
+            encodings: vec![EncodingType::Gzip],
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ServerError {
+    #[error("couldn't bind the listener: {0}")]
+    Bind(#[source] io::Error),
+    #[error("couldn't start the worker threads: {0}")]
+    Pool(#[from] PoolCreationError),
+}
+
+// Sets up everything that has to be known before the server starts, then
+// binds it: `App::builder().workers(16).bind("0.0.0.0:8080")?`.
+#[derive(Debug, Default)]
+pub struct AppBuilder {
+    config: ServerConfig,
+}
+
+impl AppBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn config(mut self, config: ServerConfig) -> Self {
+        self.config = config;
+
+        self
+    }
+
+    pub fn workers(mut self, workers: usize) -> Self {
+        self.config.workers = workers;
+
+        self
+    }
+
+    pub fn worker_name(mut self, name: impl Into<String>) -> Self {
+        self.config.worker_name = name.into();
+
+        self
+    }
+
+    pub fn worker_stack_size(mut self, bytes: usize) -> Self {
+        self.config.worker_stack_size = Some(bytes);
+
+        self
+    }
+
+    pub fn keep_alive(mut self, timeout: Duration) -> Self {
+        self.config.keep_alive = timeout;
+
+        self
+    }
+
+    pub fn max_requests_per_connection(mut self, max: usize) -> Self {
+        self.config.max_requests_per_connection = max;
+
+        self
+    }
+
+    // Request size limits. The body size can be raised or lowered for a
+    // single route with `Route::max_body_size`.
+    pub fn limits(mut self, limits: Limits) -> Self {
+        self.config.limits = limits;
+
+        self
+    }
+
+    pub fn timeouts(mut self, timeouts: Timeouts) -> Self {
+        self.config.timeouts = timeouts;
+
+        self
+    }
+
+    pub fn encodings(mut self, encodings: Vec<EncodingType>) -> Self {
+        self.config.encodings = encodings;
+
+        self
+    }
+
+    pub fn bind(self, addr: impl ToSocketAddrs) -> Result<App, ServerError> {
+        let listener = TcpListener::bind(addr).map_err(ServerError::Bind)?;
+
+        self.listener(listener)
+    }
+
+    // Serves on a listener bound elsewhere, e.g. one inherited from a
+    // supervisor or bound to port 0 by a test.
+    pub fn listener(self, listener: TcpListener) -> Result<App, ServerError> {
+        App::from_config(listener, self.config)
+    }
+}
@@ -0,0 +1,23 @@
+// Bounds on what a client may send. Exceeding them is answered with 414 for
+// the request target, 431 for the header section and 413 for the body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    pub max_uri_length: usize,
+    // Total size of the header lines with their line endings, not counting
+    // the blank line that ends the section. Also applied to chunked
+    // trailers.
+    pub max_header_bytes: usize,
+    pub max_headers: usize,
+    pub max_body_size: u64,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_uri_length: 8 * 1024,
+            max_header_bytes: 16 * 1024,
+            max_headers: 100,
+            max_body_size: 10 * 1024 * 1024,
+        }
+    }
+}
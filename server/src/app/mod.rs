@@ -1,84 +1,166 @@
 use std::{
     collections::HashMap,
     error::Error,
-    io::Write,
-    net::{TcpListener, TcpStream, ToSocketAddrs},
+    fmt,
+    io::{self, BufRead, BufReader, BufWriter},
+    marker::PhantomData,
+    mem,
+    net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    panic::{self, AssertUnwindSafe},
     sync::{
         Arc,
         atomic::{AtomicBool, Ordering},
     },
+    time::Instant,
 };
 
 use crate::{
+    app::{
+        config::{AppBuilder, ServerConfig, ServerError},
+        limits::Limits,
+        state::Extensions,
+        timeouts::{ReadTimer, TimedReader, Timeouts},
+    },
+    extract::FromRequest,
+    middleware::{Layer, Middleware, Next},
     models::{
-        encoding::EncodingType, method::Method, request::Request, response::Response,
+        encoding::EncodingType,
+        headers::Header,
+        method::Method,
+        request::{ParseError, Request},
+        response::{IntoResponse, Response},
         status::Status,
+        version::Version,
+    },
+    router::{
+        Router, pattern,
+        tree::{RouteError, RouteTree},
     },
-    router::Router,
-    thread_pool::ThreadPool,
+    thread_pool::{ThreadPool, panic_message},
 };
 
-#[derive(Debug)]
+pub mod config;
+pub mod limits;
+pub mod state;
+pub mod timeouts;
+
 pub struct App {
     listener: TcpListener,
-    routes: HashMap<String, MethodHandlerMap>,
+    routes: RouteTree,
+    route_errors: Vec<RouteError>,
     pool: ThreadPool,
-    encoding_types: Vec<EncodingType>,
+    state: Arc<Extensions>,
+    layers: Vec<Layer>,
+    config: ServerConfig,
     shutdown_flag: Arc<AtomicBool>,
 }
 
 impl App {
+    // Binds with the default configuration, panicking if the server can't
+    // start. Use `App::bind` or `App::builder` to handle that instead.
     pub fn new<T: ToSocketAddrs>(addr: T) -> Self {
-        Self {
-            listener: TcpListener::bind(addr).expect("Invalid bind address."),
-            routes: HashMap::new(),
-            pool: ThreadPool::new(5),
-            encoding_types: vec![EncodingType::Gzip],
-            shutdown_flag: Arc::new(AtomicBool::new(false)),
+        App::bind(addr).unwrap_or_else(|e| panic!("Couldn't start the server: {}", e))
+    }
+
+    pub fn bind<T: ToSocketAddrs>(addr: T) -> Result<Self, ServerError> {
+        AppBuilder::new().bind(addr)
+    }
+
+    pub fn from_listener(listener: TcpListener) -> Result<Self, ServerError> {
+        AppBuilder::new().listener(listener)
+    }
+
+    pub fn builder() -> AppBuilder {
+        AppBuilder::new()
+    }
+
+    fn from_config(listener: TcpListener, config: ServerConfig) -> Result<Self, ServerError> {
+        let mut pool = ThreadPool::builder(config.workers).name(&config.worker_name);
+        if let Some(stack_size) = config.worker_stack_size {
+            pool = pool.stack_size(stack_size);
         }
+
+        Ok(Self {
+            listener,
+            routes: RouteTree::default(),
+            route_errors: Vec::new(),
+            pool: pool.build()?,
+            state: Arc::new(Extensions::default()),
+            layers: Vec::new(),
+            config,
+            shutdown_flag: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    // Registers a value that handlers can reach through `Request::state`.
+    // Each type can be registered once; a later value of the same type
+    // replaces the earlier one.
+    pub fn with_state<S: Send + Sync + 'static>(mut self, state: S) -> Self {
+        Arc::make_mut(&mut self.state).insert(state);
+
+        self
+    }
+
+    // Wraps every request, including those answered with 404 or 405.
+    // Middleware runs in the order it is added.
+    pub fn layer(mut self, middleware: impl Middleware) -> Self {
+        self.layers.push(Arc::new(middleware));
+
+        self
     }
 
-    pub fn get(self, route: impl Into<String>, handler: RequestHandler) -> Self {
-        self.add_route(Method::Get, route, handler)
+    pub fn get<Args>(self, route: impl Into<String>, handler: impl Handler<Args>) -> Self {
+        self.add_route(Method::Get, route, handler.into_endpoint())
     }
 
     #[allow(dead_code)]
-    pub fn post(self, route: impl Into<String>, handler: RequestHandler) -> Self {
-        self.add_route(Method::Post, route, handler)
+    pub fn post<Args>(self, route: impl Into<String>, handler: impl Handler<Args>) -> Self {
+        self.add_route(Method::Post, route, handler.into_endpoint())
     }
 
     #[allow(dead_code)]
-    pub fn patch(self, route: impl Into<String>, handler: RequestHandler) -> Self {
-        self.add_route(Method::Patch, route, handler)
+    pub fn patch<Args>(self, route: impl Into<String>, handler: impl Handler<Args>) -> Self {
+        self.add_route(Method::Patch, route, handler.into_endpoint())
     }
 
     #[allow(dead_code)]
-    pub fn put(self, route: impl Into<String>, handler: RequestHandler) -> Self {
-        self.add_route(Method::Put, route, handler)
+    pub fn put<Args>(self, route: impl Into<String>, handler: impl Handler<Args>) -> Self {
+        self.add_route(Method::Put, route, handler.into_endpoint())
     }
 
     #[allow(dead_code)]
-    pub fn delete(self, route: impl Into<String>, handler: RequestHandler) -> Self {
-        self.add_route(Method::Delete, route, handler)
+    pub fn delete<Args>(self, route: impl Into<String>, handler: impl Handler<Args>) -> Self {
+        self.add_route(Method::Delete, route, handler.into_endpoint())
     }
 
-    fn add_route(
-        mut self,
-        method: Method,
-        route: impl Into<String>,
-        handler: RequestHandler,
-    ) -> Self {
-        let entry = self.routes.entry(route.into()).or_default();
-        entry.entry(method).or_insert_with(|| handler);
+    #[allow(dead_code)]
+    pub fn head<Args>(self, route: impl Into<String>, handler: impl Handler<Args>) -> Self {
+        self.add_route(Method::Head, route, handler.into_endpoint())
+    }
 
-        Self {
-            routes: self.routes,
-            ..self
+    #[allow(dead_code)]
+    pub fn options<Args>(self, route: impl Into<String>, handler: impl Handler<Args>) -> Self {
+        self.add_route(Method::Options, route, handler.into_endpoint())
+    }
+
+    fn add_route(mut self, method: Method, route: impl Into<String>, endpoint: Endpoint) -> Self {
+        if let Err(e) = self.routes.insert(method, &route.into(), endpoint) {
+            self.route_errors.push(e);
         }
+
+        self
     }
 
-    pub fn build(self) -> Arc<Self> {
-        Arc::new(self)
+    pub fn build(mut self) -> Result<Arc<Self>, RouteError> {
+        if !self.route_errors.is_empty() {
+            return Err(self.route_errors.remove(0));
+        }
+
+        Ok(Arc::new(self))
     }
 
     pub fn run(self: Arc<Self>) {
@@ -107,62 +189,249 @@ impl App {
     }
 
     fn handle_connection(&self, mut stream: TcpStream) -> Result<(), Box<dyn Error>> {
-        let Ok(req) = Request::try_from(&mut stream) else {
-            return Ok(());
-        };
+        stream.set_write_timeout(timeouts::enabled(self.config.timeouts.write))?;
+
+        // Shared by every request on the connection so bytes of a pipelined
+        // request read ahead of time are not lost. Streaming routes hold it
+        // while their handler runs.
+        let timer = ReadTimer::default();
+        let mut reader: Box<dyn BufRead + Send> = Box::new(BufReader::new(TimedReader::new(
+            stream.try_clone()?,
+            timer.clone(),
+        )));
+
+        let max_requests = self.config.max_requests_per_connection.max(1);
+        for served in 1..=max_requests {
+            // An idle connection is closed quietly. A new one gets as long
+            // as a request head would to start sending. Once a request
+            // starts arriving the clock runs for the head and then the body.
+            let idle = if served == 1 {
+                self.config.timeouts.header_read
+            } else {
+                self.config.keep_alive
+            };
+            timer.set(idle, None);
+            if !matches!(reader.fill_buf(), Ok(buf) if !buf.is_empty()) {
+                return Ok(());
+            }
 
-        let route: Vec<_> = req.path.split("/").filter(|x| !x.is_empty()).collect::<_>();
+            let started = Instant::now();
+            let deadline = Timeouts::deadline(started, self.config.timeouts.request);
+            let head_deadline = Timeouts::deadline(started, self.config.timeouts.header_read);
+            timer.set(
+                self.config.timeouts.header_read,
+                deadline.into_iter().chain(head_deadline).min(),
+            );
 
-        let route = match route.first() {
-            Some(s) => String::from(*s),
-            None => String::from("/"),
-        };
+            let mut req = match Request::parse_head(&mut reader, &self.config.limits) {
+                Ok(req) => req,
+                Err(e) => {
+                    App::reject(e, &mut stream);
+                    return Ok(());
+                }
+            };
+
+            let endpoint = self.endpoint(&req);
+            let streaming = endpoint.is_some_and(|e| e.stream_body);
+            let limits = Limits {
+                max_body_size: endpoint
+                    .and_then(|e| e.max_body_size)
+                    .unwrap_or(self.config.limits.max_body_size),
+                ..self.config.limits
+            };
+
+            timer.set(self.config.timeouts.body_read, deadline);
+            let body = if streaming {
+                req.check_body_size(limits.max_body_size)
+            } else {
+                req.read_body(&mut reader, &limits)
+            };
+            if let Err(e) = body {
+                App::reject(e, &mut stream);
+                return Ok(());
+            }
+            if streaming {
+                let reader = mem::replace(&mut reader, Box::new(io::empty()));
+                req.attach_body_stream(reader, &limits);
+            }
 
-        let response = Response::default();
+            req.state = Arc::clone(&self.state);
+
+            // A panic or error in a handler or middleware costs this request
+            // a 500, not the connection or the worker.
+            let run = || Next::new(&self.layers, &|req| self.dispatch(req)).run(&mut req);
+            let mut res = panic::catch_unwind(AssertUnwindSafe(run)).unwrap_or_else(|payload| {
+                eprintln!(
+                    "Handler for {} /{} panicked: {}",
+                    req.method,
+                    req.path,
+                    panic_message(&*payload)
+                );
+                Ok(App::error_response(
+                    Status::InternalServerError,
+                    Status::InternalServerError.reason(),
+                ))
+            });
+
+            // Whatever a streaming handler left unread can't be told apart
+            // from the next request, so the connection is closed after it.
+            let mut body_finished = true;
+            if let Some(body) = req.detach_body_stream() {
+                if let Some(status) = body.error_status() {
+                    res = Ok(App::error_response(status, status.reason()));
+                }
+                body_finished = body.is_finished();
+                reader = body.into_parts().0;
+            }
+            let mut res = res.unwrap_or_else(|e| {
+                eprintln!("Handler for {} /{} failed: {}", req.method, req.path, e);
+                App::error_response(
+                    Status::InternalServerError,
+                    Status::InternalServerError.reason(),
+                )
+            });
+
+            // HTTP/1.0 clients find the end of a body of unknown length by the
+            // connection closing.
+            let keep_alive = req.keep_alive()
+                && body_finished
+                && (req.version == Version::Http11 || res.body_length().is_some())
+                && served < max_requests
+                && !self.config.keep_alive.is_zero()
+                && !self.shutdown_flag.load(Ordering::SeqCst)
+                && !res
+                    .headers()
+                    .get(Header::Connection)
+                    .is_some_and(|v| v.eq_ignore_ascii_case("close"));
+
+            res.headers_mut().insert(
+                Header::Connection,
+                if keep_alive { "keep-alive" } else { "close" },
+            );
+            if !App::write_response(res, &req, &mut stream) || !keep_alive {
+                break;
+            }
+        }
 
-        let Some((_, route_handler)) = self.routes.get_key_value(&route) else {
-            App::send_404(&req, &mut stream);
-            return Ok(());
+        Ok(())
+    }
+
+    // The endpoint a request will be dispatched to, if it has one of its
+    // own, so its body can be read according to the route's options.
+    fn endpoint(&self, req: &Request) -> Option<&Endpoint> {
+        if req.is_asterisk() {
+            return None;
+        }
+
+        self.routes
+            .find(&req.path)
+            .and_then(|(handlers, _)| handlers.get(&req.method))
+    }
+
+    // Answers a request that couldn't be read, unless the client is gone.
+    fn reject(e: ParseError, stream: &mut TcpStream) {
+        if let Some(status) = e.status() {
+            let res = App::error_response(status, e.to_string());
+            App::write_response(res, &Request::default(), stream);
+        }
+    }
+
+    fn error_response(status: Status, message: impl Into<String>) -> Response {
+        Response::default()
+            .status(status)
+            .header(Header::Connection, "close")
+            .body(message.into().into_bytes())
+    }
+
+    fn dispatch(&self, req: &mut Request) -> ServerResponse {
+        // `OPTIONS *` asks about the server rather than a route, and there
+        // is nothing to say beyond that it's up.
+        if req.is_asterisk() {
+            return Response::default().status(Status::NoContent).into();
+        }
+
+        // Routes are matched against decoded segments, split before decoding
+        // so an escaped `/` can't split a segment in two.
+        let Some(segments) = pattern::decode_segments(&req.path) else {
+            return Response::default()
+                .status(Status::BadRequest)
+                .body(b"Invalid path".to_vec())
+                .into();
+        };
+        let Some((route_handler, params)) = self.routes.find_segments(&segments) else {
+            return Response::default().status(Status::NotFound).into();
         };
 
-        let Some((_, handler)) = route_handler.get_key_value(&req.method) else {
-            App::send_404(&req, &mut stream);
-            return Ok(());
+        req.params = params;
+
+        // HEAD and OPTIONS are answered automatically unless the route
+        // registers its own handler for them.
+        let endpoint = match (route_handler.get(&req.method), req.method) {
+            (Some(endpoint), _) => endpoint,
+            (None, Method::Head) if route_handler.contains_key(&Method::Get) => {
+                &route_handler[&Method::Get]
+            }
+            (None, Method::Options) => {
+                return Response::default()
+                    .status(Status::NoContent)
+                    .header(Header::Allow, App::allowed_methods(route_handler))
+                    .into();
+            }
+            (None, _) => {
+                return Response::default()
+                    .status(Status::MethodNotAllowed)
+                    .header(Header::Allow, App::allowed_methods(route_handler))
+                    .into();
+            }
         };
 
-        match handler(&req, response) {
-            Ok(res) => {
-                if let Err(e) = stream.write(&res.to_bytes()) {
-                    eprintln!("Failed to write response: {:?}", e);
-                }
+        let handler = |req: &mut Request| (endpoint.handler)(req, Response::default());
+
+        Next::new(&endpoint.layers, &handler).run(req)
+    }
+
+    fn write_response(res: Response, req: &Request, stream: &mut TcpStream) -> bool {
+        let mut out = BufWriter::new(stream);
+
+        match res.write_to(&mut out, req) {
+            Ok(()) => true,
+            Err(e) => {
+                eprintln!("Failed to write response: {:?}", e);
+                false
             }
-            Err(err) => return Err(err),
         }
+    }
 
-        Ok(())
+    fn allowed_methods(route_handler: &MethodHandlerMap) -> String {
+        let mut allowed = route_handler.keys().copied().collect::<Vec<_>>();
+
+        if route_handler.contains_key(&Method::Get) {
+            allowed.push(Method::Head);
+        }
+        allowed.push(Method::Options);
+
+        allowed.sort();
+        allowed.dedup();
+
+        allowed
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
     }
 
     pub fn with_router(mut self, router: Router) -> Self {
-        for (route, handlers) in router.into_routes() {
-            let entry = self.routes.entry(route).or_default();
-            for (method, handler) in handlers {
-                entry.insert(method, handler);
+        for (route, method, endpoint) in router.into_routes() {
+            if let Err(e) = self.routes.insert(method, &route, endpoint) {
+                self.route_errors.push(e);
             }
         }
 
         self
     }
 
-    fn send_404(_: &Request, stream: &mut TcpStream) {
-        let res = Response::default().status(Status::NotFound).to_bytes();
-
-        if let Err(e) = stream.write_all(&res) {
-            eprintln!("Failed to write response: {:?}", e); // Prevent shutdown on a failed write
-        }
-    }
-
     pub fn get_encoding(&self) -> EncodingType {
-        if self.encoding_types.contains(&EncodingType::Gzip) {
+        if self.config.encodings.contains(&EncodingType::Gzip) {
             EncodingType::Gzip
         } else {
             EncodingType::None
@@ -177,8 +446,155 @@ impl App {
     }
 }
 
-pub type MethodHandlerMap = HashMap<Method, RequestHandler>;
+impl fmt::Debug for App {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("App")
+            .field("listener", &self.listener)
+            .field("routes", &self.routes)
+            .field("pool", &self.pool)
+            .field("state", &self.state)
+            .field("layers", &self.layers.len())
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
+}
+
+pub type MethodHandlerMap = HashMap<Method, Endpoint>;
+
+// A registered handler together with the middleware of the routers it was
+// mounted under, outermost first.
+#[derive(Clone)]
+pub struct Endpoint {
+    pub handler: RequestHandler,
+    pub layers: Vec<Layer>,
+    pub stream_body: bool,
+    pub max_body_size: Option<u64>,
+}
+
+impl Endpoint {
+    pub fn new(handler: RequestHandler) -> Self {
+        Self {
+            handler,
+            layers: Vec::new(),
+            stream_body: false,
+            max_body_size: None,
+        }
+    }
+}
+
+pub type RequestHandler = Arc<dyn Fn(&Request, Response) -> ServerResponse + Send + Sync>;
+
+// Anything that can be registered as a route handler: plain
+// `fn(&Request, Response)` functions and closures capturing state, or
+// functions taking up to six extractors and returning any `IntoResponse`.
+pub trait Handler<Args>: Send + Sync + 'static {
+    fn into_handler(self) -> RequestHandler;
+
+    fn into_endpoint(self) -> Endpoint
+    where
+        Self: Sized,
+    {
+        Endpoint::new(self.into_handler())
+    }
+}
+
+// A handler with per-route options, registered like any other handler:
+// `app.post("upload", Route::new(upload).stream_body())`.
+pub struct Route<H> {
+    handler: H,
+    stream_body: bool,
+    max_body_size: Option<u64>,
+}
+
+impl<H> Route<H> {
+    pub fn new(handler: H) -> Self {
+        Self {
+            handler,
+            stream_body: false,
+            max_body_size: None,
+        }
+    }
+
+    // Leaves the body on the connection for the handler to read through
+    // `Request::body_reader` instead of buffering it in `Request::body`.
+    pub fn stream_body(mut self) -> Self {
+        self.stream_body = true;
+
+        self
+    }
+
+    // Overrides the app's `Limits::max_body_size` for this route.
+    pub fn max_body_size(mut self, bytes: u64) -> Self {
+        self.max_body_size = Some(bytes);
+
+        self
+    }
+}
+
+pub struct RouteMarker<Args>(PhantomData<Args>);
+
+impl<H, Args> Handler<RouteMarker<Args>> for Route<H>
+where
+    H: Handler<Args>,
+    Args: 'static,
+{
+    fn into_handler(self) -> RequestHandler {
+        self.handler.into_handler()
+    }
+
+    fn into_endpoint(self) -> Endpoint {
+        Endpoint {
+            stream_body: self.stream_body,
+            max_body_size: self.max_body_size,
+            ..self.handler.into_endpoint()
+        }
+    }
+}
+
+// Marker for handlers that take the raw `Request` and default `Response`.
+pub struct RawHandler;
+
+impl<F, R> Handler<RawHandler> for F
+where
+    F: Fn(&Request, Response) -> R + Send + Sync + 'static,
+    R: IntoResponse<Response>,
+{
+    fn into_handler(self) -> RequestHandler {
+        Arc::new(move |req, res| self(req, res).into_response())
+    }
+}
+
+macro_rules! impl_handler {
+    ($($extractor:ident),*) => {
+        #[allow(non_snake_case)]
+        impl<F, R, $($extractor,)*> Handler<($($extractor,)*)> for F
+        where
+            F: Fn($($extractor),*) -> R + Send + Sync + 'static,
+            R: IntoResponse<Response>,
+            $($extractor: FromRequest,)*
+        {
+            fn into_handler(self) -> RequestHandler {
+                Arc::new(move |_req, _| {
+                    $(
+                        let $extractor = match $extractor::from_request(_req) {
+                            Ok(value) => value,
+                            Err(rejection) => return rejection.into_response(),
+                        };
+                    )*
+
+                    self($($extractor),*).into_response()
+                })
+            }
+        }
+    };
+}
 
-pub type RequestHandler = fn(&Request, Response) -> Result<Response, Box<dyn Error>>;
+impl_handler!();
+impl_handler!(T1);
+impl_handler!(T1, T2);
+impl_handler!(T1, T2, T3);
+impl_handler!(T1, T2, T3, T4);
+impl_handler!(T1, T2, T3, T4, T5);
+impl_handler!(T1, T2, T3, T4, T5, T6);
 
 pub type ServerResponse = Result<Response, Box<dyn Error>>;
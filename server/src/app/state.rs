@@ -0,0 +1,43 @@
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    fmt,
+    sync::Arc,
+};
+
+// Type map holding one value per type, so independent modules can register
+// their own state on the `App` without coordinating.
+#[derive(Default, Clone)]
+pub struct Extensions {
+    map: HashMap<TypeId, Arc<dyn Any + Send + Sync>>,
+}
+
+impl Extensions {
+    pub fn insert<S: Send + Sync + 'static>(&mut self, value: S) {
+        self.map.insert(TypeId::of::<S>(), Arc::new(value));
+    }
+
+    pub fn get<S: Send + Sync + 'static>(&self) -> Option<&S> {
+        self.map
+            .get(&TypeId::of::<S>())
+            .and_then(|value| value.downcast_ref())
+    }
+
+    pub fn get_arc<S: Send + Sync + 'static>(&self) -> Option<Arc<S>> {
+        self.map
+            .get(&TypeId::of::<S>())
+            .and_then(|value| Arc::clone(value).downcast().ok())
+    }
+
+    pub fn contains<S: Send + Sync + 'static>(&self) -> bool {
+        self.map.contains_key(&TypeId::of::<S>())
+    }
+}
+
+impl fmt::Debug for Extensions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Extensions")
+            .field("len", &self.map.len())
+            .finish()
+    }
+}
@@ -0,0 +1,100 @@
+use std::{
+    io::{self, ErrorKind, Read},
+    net::TcpStream,
+    sync::{Arc, Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+// How long a client gets to send its request and to take the response.
+// A client that runs out of time while sending is answered with 408.
+// A zero duration turns that timeout off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timeouts {
+    // Time to send the request line and headers, counted from their first
+    // byte, so trickling a byte at a time doesn't keep the head open.
+    pub header_read: Duration,
+    // Longest wait for the next piece of the body.
+    pub body_read: Duration,
+    // Longest wait for the client to accept more of the response.
+    pub write: Duration,
+    // Time to send the whole request, head and body.
+    pub request: Duration,
+}
+
+impl Default for Timeouts {
+    fn default() -> Self {
+        Self {
+            header_read: Duration::from_secs(10),
+            body_read: Duration::from_secs(30),
+            write: Duration::from_secs(30),
+            request: Duration::from_secs(300),
+        }
+    }
+}
+
+impl Timeouts {
+    // When a timeout started at `started` runs out, if it's on at all.
+    pub(crate) fn deadline(started: Instant, timeout: Duration) -> Option<Instant> {
+        enabled(timeout).map(|timeout| started + timeout)
+    }
+}
+
+// The OS rejects a zero socket timeout, so zero means no timeout here.
+pub(crate) fn enabled(timeout: Duration) -> Option<Duration> {
+    (!timeout.is_zero()).then_some(timeout)
+}
+
+// Reads from the connection, failing with `TimedOut` when a single read
+// waits longer than the current per-read timeout or the deadline passes.
+// Both are changed through the `ReadTimer` as the request moves along.
+pub(crate) struct TimedReader {
+    stream: TcpStream,
+    timer: ReadTimer,
+}
+
+#[derive(Clone, Default)]
+pub(crate) struct ReadTimer {
+    state: Arc<Mutex<(Duration, Option<Instant>)>>,
+}
+
+impl TimedReader {
+    pub(crate) fn new(stream: TcpStream, timer: ReadTimer) -> Self {
+        Self { stream, timer }
+    }
+}
+
+impl Read for TimedReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let (per_read, deadline) = self.timer.get();
+
+        let left = deadline
+            .map(|deadline| {
+                deadline
+                    .checked_duration_since(Instant::now())
+                    .filter(|left| !left.is_zero())
+                    .ok_or_else(|| io::Error::new(ErrorKind::TimedOut, "request deadline passed"))
+            })
+            .transpose()?;
+        let timeout = match (enabled(per_read), left) {
+            (Some(per_read), Some(left)) => Some(per_read.min(left)),
+            (per_read, left) => per_read.or(left),
+        };
+
+        self.stream.set_read_timeout(timeout)?;
+        self.stream.read(buf).map_err(|e| match e.kind() {
+            // Unix reports an expired socket timeout as `WouldBlock`.
+            ErrorKind::WouldBlock => io::Error::new(ErrorKind::TimedOut, e),
+            _ => e,
+        })
+    }
+}
+
+impl ReadTimer {
+    pub(crate) fn set(&self, per_read: Duration, deadline: Option<Instant>) {
+        *self.state.lock().unwrap_or_else(PoisonError::into_inner) = (per_read, deadline);
+    }
+
+    fn get(&self) -> (Duration, Option<Instant>) {
+        *self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
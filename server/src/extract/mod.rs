@@ -0,0 +1,134 @@
+use std::{error::Error, fmt, sync::Arc};
+
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::models::{
+    content_type::ContentType,
+    request::Request,
+    response::{IntoResponse, Response},
+    status::Status,
+};
+
+// Pulls a typed value out of a request. Handlers can take any number of
+// extractors as arguments; the first one to fail short-circuits the handler
+// and its `Rejection` is sent instead.
+pub trait FromRequest: Sized {
+    fn from_request(req: &Request) -> Result<Self, Rejection>;
+}
+
+#[derive(Debug)]
+pub struct Rejection {
+    pub status: Status,
+    pub message: String,
+}
+
+impl Rejection {
+    pub fn new(status: Status, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.status, self.message)
+    }
+}
+
+impl Error for Rejection {}
+
+impl IntoResponse<Response> for Rejection {
+    fn into_response(self) -> Result<Response, Box<dyn Error>> {
+        Ok(Response::default()
+            .status(self.status)
+            .content_type(ContentType::TextPlain)
+            .body(self.message.into_bytes()))
+    }
+}
+
+// Captured route parameters. A single parameter deserializes straight into
+// `T` (e.g. `Path<u32>`), several deserialize into a struct or map keyed by
+// parameter name.
+#[derive(Debug)]
+pub struct Path<T>(pub T);
+
+impl<T: DeserializeOwned> FromRequest for Path<T> {
+    fn from_request(req: &Request) -> Result<Self, Rejection> {
+        let encoded = serde_urlencoded::to_string(&req.params)
+            .map_err(|e| Rejection::new(Status::BadRequest, e.to_string()))?;
+
+        if req.params.len() == 1
+            && let Ok(mut values) = serde_urlencoded::from_str::<Vec<(String, T)>>(&encoded)
+        {
+            let (_, value) = values.remove(0);
+            return Ok(Path(value));
+        }
+
+        serde_urlencoded::from_str(&encoded)
+            .map(Path)
+            .map_err(|e| Rejection::new(Status::BadRequest, format!("Invalid path: {}", e)))
+    }
+}
+
+#[derive(Debug)]
+pub struct Query<T>(pub T);
+
+impl<T: DeserializeOwned> FromRequest for Query<T> {
+    fn from_request(req: &Request) -> Result<Self, Rejection> {
+        serde_urlencoded::from_str(&req.query_string)
+            .map(Query)
+            .map_err(|e| Rejection::new(Status::BadRequest, format!("Invalid query: {}", e)))
+    }
+}
+
+#[derive(Debug)]
+pub struct Json<T>(pub T);
+
+impl<T: DeserializeOwned> FromRequest for Json<T> {
+    fn from_request(req: &Request) -> Result<Self, Rejection> {
+        if !req
+            .content_type()
+            .starts_with(&ContentType::Json.to_string())
+        {
+            return Err(Rejection::new(
+                Status::UnsupportedMediaType,
+                "Expected request with `Content-Type: application/json`",
+            ));
+        }
+
+        serde_json::from_slice(&req.body).map(Json).map_err(|e| {
+            let status = match e.classify() {
+                serde_json::error::Category::Data => Status::UnprocessableContent,
+                _ => Status::BadRequest,
+            };
+
+            Rejection::new(status, format!("Invalid JSON body: {}", e))
+        })
+    }
+}
+
+impl<T: Serialize> IntoResponse<Response> for Json<T> {
+    fn into_response(self) -> Result<Response, Box<dyn Error>> {
+        let body = serde_json::to_vec(&self.0)?;
+
+        Ok(Response::default()
+            .content_type(ContentType::Json)
+            .body(body))
+    }
+}
+
+#[derive(Debug)]
+pub struct State<S>(pub Arc<S>);
+
+impl<S: Send + Sync + 'static> FromRequest for State<S> {
+    fn from_request(req: &Request) -> Result<Self, Rejection> {
+        req.state.get_arc::<S>().map(State).ok_or_else(|| {
+            Rejection::new(
+                Status::InternalServerError,
+                format!("State `{}` is not registered", std::any::type_name::<S>()),
+            )
+        })
+    }
+}
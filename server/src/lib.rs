@@ -1,4 +1,6 @@
 pub mod app;
+pub mod extract;
+pub mod middleware;
 pub mod models;
 pub mod router;
 pub mod thread_pool;
@@ -0,0 +1,41 @@
+use std::sync::Arc;
+
+use crate::{app::ServerResponse, models::request::Request};
+
+// Runs around handlers. A middleware can inspect or mutate the request,
+// short-circuit by returning a response without calling `next`, or
+// post-process the response that `next.run(req)` produces.
+pub trait Middleware: Send + Sync + 'static {
+    fn handle(&self, req: &mut Request, next: Next<'_>) -> ServerResponse;
+}
+
+impl<F> Middleware for F
+where
+    F: Fn(&mut Request, Next<'_>) -> ServerResponse + Send + Sync + 'static,
+{
+    fn handle(&self, req: &mut Request, next: Next<'_>) -> ServerResponse {
+        self(req, next)
+    }
+}
+
+pub type Layer = Arc<dyn Middleware>;
+
+// The rest of the pipeline: the remaining middleware followed by the
+// endpoint they wrap.
+pub struct Next<'a> {
+    layers: &'a [Layer],
+    endpoint: &'a dyn Fn(&mut Request) -> ServerResponse,
+}
+
+impl<'a> Next<'a> {
+    pub fn new(layers: &'a [Layer], endpoint: &'a dyn Fn(&mut Request) -> ServerResponse) -> Self {
+        Self { layers, endpoint }
+    }
+
+    pub fn run(self, req: &mut Request) -> ServerResponse {
+        match self.layers.split_first() {
+            Some((layer, rest)) => layer.handle(req, Next::new(rest, self.endpoint)),
+            None => (self.endpoint)(req),
+        }
+    }
+}
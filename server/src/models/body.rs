@@ -0,0 +1,144 @@
+use std::{
+    fmt,
+    io::{self, Read, Write},
+};
+
+const CHUNK_SIZE: usize = 8 * 1024;
+
+// Response payload. Buffered bodies and readers of known length are sent
+// with Content-Length; anything else goes out with chunked encoding.
+pub enum Body {
+    Bytes(Vec<u8>),
+    Reader {
+        reader: Box<dyn Read + Send>,
+        length: Option<u64>,
+    },
+    Chunks(Box<dyn Iterator<Item = Vec<u8>> + Send>),
+}
+
+impl Body {
+    pub fn len(&self) -> Option<u64> {
+        match self {
+            Body::Bytes(bytes) => Some(bytes.len() as u64),
+            Body::Reader { length, .. } => *length,
+            Body::Chunks(_) => None,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == Some(0)
+    }
+
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Body::Bytes(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    // Writes the payload as-is when its length is known, otherwise either
+    // chunk-encoded or, for clients that can't decode chunks, raw until the
+    // connection closes. Streamed bodies are flushed piece by piece so the
+    // client sees each one as soon as it's produced.
+    pub fn write_to(self, out: &mut impl Write, chunked: bool) -> io::Result<()> {
+        match self {
+            Body::Bytes(bytes) => out.write_all(&bytes),
+            Body::Reader {
+                reader,
+                length: Some(length),
+            } => {
+                let copied = copy_flushing(&mut reader.take(length), out, false)?;
+                if copied < length {
+                    return Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        format!("body ended after {} of {} bytes", copied, length),
+                    ));
+                }
+                Ok(())
+            }
+            Body::Reader {
+                mut reader,
+                length: None,
+            } => {
+                copy_flushing(&mut reader, out, chunked)?;
+                finish_chunks(out, chunked)
+            }
+            Body::Chunks(chunks) => {
+                for chunk in chunks {
+                    write_chunk(out, &chunk, chunked)?;
+                }
+                finish_chunks(out, chunked)
+            }
+        }
+    }
+
+    // Whether the payload is produced while it's being sent.
+    pub fn is_streamed(&self) -> bool {
+        !matches!(self, Body::Bytes(_))
+    }
+}
+
+// Copies the reader out a piece at a time, returning the number of bytes
+// read.
+fn copy_flushing(reader: &mut impl Read, out: &mut impl Write, chunked: bool) -> io::Result<u64> {
+    let mut buf = vec![0; CHUNK_SIZE];
+    let mut copied = 0;
+    loop {
+        let read = match reader.read(&mut buf) {
+            Ok(0) => return Ok(copied),
+            Ok(read) => read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        write_chunk(out, &buf[..read], chunked)?;
+        copied += read as u64;
+    }
+}
+
+fn write_chunk(out: &mut impl Write, chunk: &[u8], chunked: bool) -> io::Result<()> {
+    // An empty chunk would mark the end of the body.
+    if chunk.is_empty() {
+        return Ok(());
+    }
+    if chunked {
+        write!(out, "{:x}\r\n", chunk.len())?;
+        out.write_all(chunk)?;
+        out.write_all(b"\r\n")?;
+    } else {
+        out.write_all(chunk)?;
+    }
+
+    out.flush()
+}
+
+fn finish_chunks(out: &mut impl Write, chunked: bool) -> io::Result<()> {
+    if chunked {
+        out.write_all(b"0\r\n\r\n")?;
+    }
+
+    Ok(())
+}
+
+impl Default for Body {
+    fn default() -> Self {
+        Body::Bytes(Vec::new())
+    }
+}
+
+impl From<Vec<u8>> for Body {
+    fn from(bytes: Vec<u8>) -> Self {
+        Body::Bytes(bytes)
+    }
+}
+
+impl fmt::Debug for Body {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Body::Bytes(bytes) => f.debug_tuple("Bytes").field(&bytes.len()).finish(),
+            Body::Reader { length, .. } => {
+                f.debug_struct("Reader").field("length", length).finish()
+            }
+            Body::Chunks(_) => f.write_str("Chunks"),
+        }
+    }
+}
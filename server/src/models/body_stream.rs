@@ -0,0 +1,156 @@
+use std::{
+    fmt,
+    io::{self, BufRead, ErrorKind, Read},
+};
+
+use crate::app::limits::Limits;
+
+use super::{
+    header_map::HeaderMap,
+    request::{ParseError, Request},
+    status::Status,
+};
+
+// How the end of a request body is found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Framing {
+    Length(u64),
+    Chunked,
+}
+
+impl Default for Framing {
+    fn default() -> Self {
+        Framing::Length(0)
+    }
+}
+
+// A request body read straight off the connection, decoding chunked
+// transfer coding and enforcing the body size limit as it goes.
+pub struct BodyStream<R = Box<dyn BufRead + Send>> {
+    reader: R,
+    framing: Framing,
+    // Bytes left in the whole body, or in the current chunk.
+    remaining: u64,
+    read: u64,
+    limits: Limits,
+    finished: bool,
+    error: Option<Status>,
+    trailers: HeaderMap,
+}
+
+impl<R: BufRead> BodyStream<R> {
+    pub fn new(reader: R, framing: Framing, limits: Limits) -> Self {
+        let remaining = match framing {
+            Framing::Length(length) => length,
+            Framing::Chunked => 0,
+        };
+
+        Self {
+            reader,
+            framing,
+            remaining,
+            read: 0,
+            limits,
+            finished: framing == Framing::Length(0),
+            error: None,
+            trailers: HeaderMap::new(),
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    // Status for the error that stopped reading, e.g. 413 once the body
+    // turned out larger than allowed or 408 when the client stalled.
+    pub fn error_status(&self) -> Option<Status> {
+        self.error
+    }
+
+    // Trailer fields of a chunked body, available once it is fully read.
+    pub fn trailers(&self) -> &HeaderMap {
+        &self.trailers
+    }
+
+    pub fn into_parts(self) -> (R, HeaderMap) {
+        (self.reader, self.trailers)
+    }
+
+    pub fn read_to_end(&mut self) -> Result<Vec<u8>, ParseError> {
+        let mut body = Vec::new();
+        let mut buf = vec![0; 8 * 1024];
+
+        loop {
+            match self.read_body(&mut buf)? {
+                0 => return Ok(body),
+                read => body.extend_from_slice(&buf[..read]),
+            }
+        }
+    }
+
+    fn read_body(&mut self, buf: &mut [u8]) -> Result<usize, ParseError> {
+        if self.finished || buf.is_empty() {
+            return Ok(0);
+        }
+
+        if self.remaining == 0 {
+            let size = Request::read_chunk_size(&mut self.reader)?;
+            if size == 0 {
+                Request::read_headers(&mut self.reader, &mut self.trailers, &self.limits)?;
+                self.finished = true;
+                return Ok(0);
+            }
+            self.remaining = size;
+        }
+
+        if self.read.saturating_add(self.remaining) > self.limits.max_body_size {
+            return Err(ParseError::BodyTooLarge);
+        }
+
+        let max = buf
+            .len()
+            .min(usize::try_from(self.remaining).unwrap_or(usize::MAX));
+        let read = match self.reader.read(&mut buf[..max]) {
+            Ok(0) => return Err(ParseError::UnexpectedEof),
+            Ok(read) => read,
+            Err(e) => return Err(ParseError::Io(e)),
+        };
+
+        self.remaining -= read as u64;
+        self.read += read as u64;
+
+        if self.remaining == 0 {
+            match self.framing {
+                Framing::Length(_) => self.finished = true,
+                Framing::Chunked => Request::read_chunk_end(&mut self.reader)?,
+            }
+        }
+
+        Ok(read)
+    }
+}
+
+impl<R: BufRead> Read for BodyStream<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.read_body(buf).map_err(|e| {
+            self.error = e.status();
+
+            match e {
+                ParseError::Io(e) => e,
+                ParseError::UnexpectedEof => io::Error::new(ErrorKind::UnexpectedEof, e),
+                e => io::Error::new(ErrorKind::InvalidData, e),
+            }
+        })
+    }
+}
+
+impl<R> fmt::Debug for BodyStream<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BodyStream")
+            .field("framing", &self.framing)
+            .field("read", &self.read)
+            .field("max_body_size", &self.limits.max_body_size)
+            .field("finished", &self.finished)
+            .finish_non_exhaustive()
+    }
+}
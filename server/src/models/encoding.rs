@@ -1,7 +1,7 @@
 use std::str::FromStr;
 use strum::Display;
 
-#[derive(Display, Debug, Hash, Eq, PartialEq, Default)]
+#[derive(Display, Debug, Clone, Copy, Hash, Eq, PartialEq, Default)]
 pub enum EncodingType {
     #[strum(to_string = "gzip")]
     Gzip,
@@ -0,0 +1,120 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum InvalidHeader {
+    #[error("invalid header name {0:?}")]
+    Name(String),
+    #[error("invalid value for header `{0}`")]
+    Value(String),
+}
+
+// Case-insensitive, multi-valued header collection. Names are stored
+// lowercased and entries keep the order they were added in.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct HeaderMap {
+    entries: Vec<(String, String)>,
+}
+
+impl HeaderMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, name: impl AsRef<str>) -> Option<&str> {
+        self.get_all(name).next()
+    }
+
+    pub fn get_all(&self, name: impl AsRef<str>) -> impl Iterator<Item = &str> {
+        let name = name.as_ref().to_ascii_lowercase();
+
+        self.entries
+            .iter()
+            .filter(move |(n, _)| *n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn contains(&self, name: impl AsRef<str>) -> bool {
+        self.get(name).is_some()
+    }
+
+    // Adds a value, keeping any existing values for the same name. Headers
+    // that can't be written out safely are dropped and logged; use
+    // `try_append` to handle them instead.
+    pub fn append(&mut self, name: impl AsRef<str>, value: impl Into<String>) {
+        if let Err(e) = self.try_append(name, value) {
+            eprintln!("Dropping header: {}", e);
+        }
+    }
+
+    // Like `append`, but fails on a name that isn't a token or a value
+    // containing CR, LF or NUL, either of which would let the value start
+    // a header of its own.
+    pub fn try_append(
+        &mut self,
+        name: impl AsRef<str>,
+        value: impl Into<String>,
+    ) -> Result<(), InvalidHeader> {
+        let (name, value) = (name.as_ref(), value.into());
+        if !is_valid_name(name) {
+            return Err(InvalidHeader::Name(name.to_string()));
+        }
+        if !is_valid_value(&value) {
+            return Err(InvalidHeader::Value(name.to_string()));
+        }
+
+        self.entries.push((name.to_ascii_lowercase(), value));
+
+        Ok(())
+    }
+
+    // Replaces every existing value for the name.
+    pub fn insert(&mut self, name: impl AsRef<str>, value: impl Into<String>) {
+        self.remove(&name);
+        self.append(name, value);
+    }
+
+    pub fn remove(&mut self, name: impl AsRef<str>) {
+        let name = name.as_ref().to_ascii_lowercase();
+
+        self.entries.retain(|(n, _)| *n != name);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries.iter().map(|(n, v)| (n.as_str(), v.as_str()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+// Names are stored lowercased; they're written out with each word
+// capitalized, e.g. `Content-Type`.
+pub fn canonical_name(name: &str) -> String {
+    name.split('-')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+// A header name must be a non-empty token (RFC 9110 §5.6.2).
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+}
+
+pub fn is_valid_value(value: &str) -> bool {
+    !value.contains(['\r', '\n', '\0'])
+}
@@ -1,6 +1,6 @@
-use strum::Display;
+use strum::{AsRefStr, Display};
 
-#[derive(Display)]
+#[derive(Display, AsRefStr)]
 pub enum Header {
     #[strum(to_string = "host")]
     Host,
@@ -14,4 +14,20 @@ pub enum Header {
     AcceptEncoding,
     #[strum(to_string = "content-length")]
     ContentLength,
+    #[strum(to_string = "content-encoding")]
+    ContentEncoding,
+    #[strum(to_string = "allow")]
+    Allow,
+    #[strum(to_string = "location")]
+    Location,
+    #[strum(to_string = "cache-control")]
+    CacheControl,
+    #[strum(to_string = "set-cookie")]
+    SetCookie,
+    #[strum(to_string = "etag")]
+    ETag,
+    #[strum(to_string = "connection")]
+    Connection,
+    #[strum(to_string = "transfer-encoding")]
+    TransferEncoding,
 }
@@ -1,6 +1,8 @@
-use strum::EnumString;
+use strum::{Display, EnumString};
 
-#[derive(Debug, Hash, Eq, PartialEq, Default, EnumString)]
+#[derive(
+    Debug, Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd, Default, Display, EnumString,
+)]
 pub enum Method {
     #[default]
     #[strum(serialize = "GET", ascii_case_insensitive)]
@@ -13,4 +15,8 @@ pub enum Method {
     Put,
     #[strum(serialize = "DELETE", ascii_case_insensitive)]
     Delete,
+    #[strum(serialize = "HEAD", ascii_case_insensitive)]
+    Head,
+    #[strum(serialize = "OPTIONS", ascii_case_insensitive)]
+    Options,
 }
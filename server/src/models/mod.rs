@@ -1,7 +1,11 @@
+pub mod body;
+pub mod body_stream;
 pub mod content_type;
 pub mod encoding;
+pub mod header_map;
 pub mod headers;
 pub mod method;
 pub mod request;
 pub mod response;
 pub mod status;
+pub mod version;
@@ -1,146 +1,510 @@
 use std::{
-    error::Error,
-    io::{BufRead, BufReader, Read},
-    net::TcpStream,
+    collections::HashMap,
+    io::{self, BufRead, ErrorKind, Read},
+    str::{self, Utf8Error},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
 };
 
-use crate::models::headers::Header;
+use bytes::Bytes;
+use thiserror::Error;
 
-use super::{encoding::EncodingType, method::Method};
+use crate::{
+    app::{limits::Limits, state::Extensions},
+    models::headers::Header,
+};
+
+use super::{
+    body_stream::{BodyStream, Framing},
+    encoding::EncodingType,
+    header_map::{self, HeaderMap},
+    method::Method,
+    status::Status,
+    version::Version,
+};
 
 #[derive(Debug, Default)]
 pub struct Request {
     pub method: Method,
+    pub version: Version,
     pub path: String,
-    pub query: String,
-    pub host: String,
-    pub user_agent: String,
-    pub accept: String,
-    pub content_type: String,
-    pub content_length: usize,
-    pub accept_encoding: Vec<EncodingType>,
-    pub body: String,
+    pub query_string: String,
+    pub query_params: Vec<(String, String)>,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+    pub trailers: HeaderMap,
+    pub params: HashMap<String, String>,
+    pub state: Arc<Extensions>,
+    framing: Framing,
+    body_stream: Option<Mutex<BodyStream>>,
+    asterisk: bool,
 }
 
-#[derive(Debug)]
-pub struct ReqError {
-    pub msg: String,
+// Room for the method and version around the request target.
+const REQUEST_LINE_OVERHEAD: usize = 32;
+const MAX_CHUNK_LINE: usize = 1024;
+
+#[derive(Debug, Error)]
+pub enum ParseError {
+    #[error("connection closed before a request was sent")]
+    ConnectionClosed,
+    #[error("connection closed in the middle of a request")]
+    UnexpectedEof,
+    #[error("malformed request line")]
+    InvalidRequestLine,
+    #[error("unsupported method `{0}`")]
+    UnsupportedMethod(String),
+    #[error("unsupported HTTP version `{0}`")]
+    UnsupportedVersion(String),
+    #[error("invalid request target")]
+    InvalidTarget,
+    #[error("request line is too long")]
+    UriTooLong,
+    #[error("malformed header line")]
+    InvalidHeader,
+    #[error("request headers are too large")]
+    HeadersTooLarge,
+    #[error("request head is not valid UTF-8")]
+    InvalidEncoding,
+    #[error("invalid Content-Length")]
+    InvalidContentLength,
+    #[error("request has both Content-Length and Transfer-Encoding")]
+    AmbiguousLength,
+    #[error("invalid Transfer-Encoding")]
+    InvalidTransferEncoding,
+    #[error("unsupported transfer coding `{0}`")]
+    UnsupportedTransferEncoding(String),
+    #[error("malformed chunk in request body")]
+    InvalidChunk,
+    #[error("request body is too large")]
+    BodyTooLarge,
+    #[error(transparent)]
+    Io(#[from] io::Error),
 }
 
-impl Request {
-    fn parse_method_and_path(strings: Vec<&str>) -> Result<(String, Method, String), ReqError> {
-        let [method, path, _]: [_; 3] = strings.try_into().ok().unwrap();
-
-        let method = match method.parse::<Method>() {
-            Ok(m) => m,
-            Err(_) => {
-                return Err(ReqError {
-                    msg: String::from("Coudn't parse method type"),
-                });
+impl ParseError {
+    // Status to answer with, or `None` when the client is gone and there is
+    // nobody to answer.
+    pub fn status(&self) -> Option<Status> {
+        match self {
+            ParseError::Io(e) if e.kind() == ErrorKind::TimedOut => Some(Status::RequestTimeout),
+            ParseError::ConnectionClosed | ParseError::Io(_) => None,
+            ParseError::UnsupportedMethod(_) | ParseError::UnsupportedTransferEncoding(_) => {
+                Some(Status::NotImplemented)
             }
-        };
+            ParseError::UnsupportedVersion(_) => Some(Status::HttpVersionNotSupported),
+            ParseError::UriTooLong => Some(Status::UriTooLong),
+            ParseError::HeadersTooLarge => Some(Status::RequestHeaderFieldsTooLarge),
+            ParseError::BodyTooLarge => Some(Status::ContentTooLarge),
+            _ => Some(Status::BadRequest),
+        }
+    }
+}
+
+impl Request {
+    // Whether this is an `OPTIONS *` request, about the server as a whole
+    // rather than any one resource.
+    pub fn is_asterisk(&self) -> bool {
+        self.asterisk
+    }
 
-        let (path_part, query_part) = path.split_once('?').unwrap_or((path, ""));
-        let path = path_part.trim_start_matches('/').to_string();
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params.get(name).map(String::as_str)
+    }
 
-        let query_value = query_part.split('=').nth(1).unwrap_or("none");
+    pub fn state<S: Send + Sync + 'static>(&self) -> Option<&S> {
+        self.state.get::<S>()
+    }
 
-        Ok((path, method, query_value.to_string()))
+    pub fn text(&self) -> Result<&str, Utf8Error> {
+        str::from_utf8(&self.body)
     }
 
-    fn parse_string_from_header(query: Header, headers: &[String]) -> String {
-        headers
-            .iter()
-            .find(|s| s.contains(&query.to_string()))
-            .and_then(|s| s.split_whitespace().last())
-            .map(ToString::to_string)
-            .unwrap_or_default()
+    // First value of a query parameter, percent-decoded. A parameter given
+    // without a value (`?flag` or `?name=`) yields `Some("")`.
+    pub fn query(&self, name: &str) -> Option<&str> {
+        self.query_all(name).next()
     }
 
-    fn parse_encodings_from_header(query: Header, headers: &[String]) -> Vec<String> {
-        headers
+    pub fn query_all(&self, name: &str) -> impl Iterator<Item = &str> {
+        self.query_params
             .iter()
-            .find(|s| s.contains(&query.to_string()))
-            .map(|s| {
-                s.split_whitespace()
-                    .map(ToString::to_string)
-                    .skip(1)
-                    .map(|w| w.trim_end_matches(',').to_string())
-                    .collect::<Vec<_>>()
-            })
+            .filter(move |(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn header(&self, name: impl AsRef<str>) -> Option<&str> {
+        self.headers.get(name)
+    }
+
+    pub fn host(&self) -> &str {
+        self.header(Header::Host).unwrap_or_default()
+    }
+
+    pub fn user_agent(&self) -> &str {
+        self.header(Header::UserAgent).unwrap_or_default()
+    }
+
+    pub fn accept(&self) -> &str {
+        self.header(Header::Accept).unwrap_or_default()
+    }
+
+    pub fn content_type(&self) -> &str {
+        self.header(Header::ContentType).unwrap_or_default()
+    }
+
+    pub fn content_length(&self) -> usize {
+        self.header(Header::ContentLength)
+            .and_then(|v| v.parse().ok())
             .unwrap_or_default()
     }
 
-    fn parse_header_and_body(
-        request: &mut Request,
-        stream: &TcpStream,
-    ) -> Result<(), Box<dyn Error>> {
-        let mut buf_reader = BufReader::new(stream);
+    // Whether the client wants the connection kept open after this request.
+    // HTTP/1.1 defaults to keep-alive and HTTP/1.0 to close; a `Connection`
+    // header overrides either.
+    pub fn keep_alive(&self) -> bool {
+        let mut tokens = self
+            .headers
+            .get_all(Header::Connection)
+            .flat_map(|v| v.split(','))
+            .map(str::trim);
 
-        // 1) Read lines until empty line -> headers
-        let mut headers = Vec::new();
-        loop {
-            let mut line = String::new();
-            let bytes_read = buf_reader.read_line(&mut line).unwrap();
-            if bytes_read == 0 || line.trim().is_empty() {
-                break; // end of headers
+        match self.version {
+            Version::Http11 => !tokens.any(|t| t.eq_ignore_ascii_case("close")),
+            Version::Http10 => tokens.any(|t| t.eq_ignore_ascii_case("keep-alive")),
+        }
+    }
+
+    // Encodings from every Accept-Encoding header, in order, ignoring any
+    // `;q=` weights.
+    pub fn accept_encoding(&self) -> Vec<EncodingType> {
+        self.headers
+            .get_all(Header::AcceptEncoding)
+            .flat_map(|v| v.split(','))
+            .filter_map(|e| e.split(';').next())
+            .map(|e| e.trim().parse::<EncodingType>().unwrap_or_default())
+            .collect()
+    }
+
+    fn parse_request_line(
+        line: &str,
+        max_uri_length: usize,
+    ) -> Result<(Method, Version, &str), ParseError> {
+        let mut parts = line.split(' ');
+        let (Some(method), Some(target), Some(version), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(ParseError::InvalidRequestLine);
+        };
+
+        if method.is_empty() || target.is_empty() {
+            return Err(ParseError::InvalidRequestLine);
+        }
+        if target.len() > max_uri_length {
+            return Err(ParseError::UriTooLong);
+        }
+
+        let version = match version.parse::<Version>() {
+            Ok(version) => version,
+            Err(_) if version.starts_with("HTTP/") => {
+                return Err(ParseError::UnsupportedVersion(version.to_string()));
             }
-            headers.push(line);
+            Err(_) => return Err(ParseError::InvalidRequestLine),
+        };
+
+        let method = method
+            .parse::<Method>()
+            .map_err(|_| ParseError::UnsupportedMethod(method.to_string()))?;
+
+        Ok((method, version, target))
+    }
+
+    // Splits an absolute-form target (`http://host/path`) into its authority
+    // and the origin-form part, which is all an origin-form target has.
+    // `*` is only allowed for OPTIONS (RFC 9112 §3.2).
+    fn split_target(method: Method, target: &str) -> Result<(Option<&str>, &str), ParseError> {
+        if target == "*" {
+            return match method {
+                Method::Options => Ok((None, target)),
+                _ => Err(ParseError::InvalidTarget),
+            };
+        }
+        if target.starts_with('/') {
+            return Ok((None, target));
+        }
+
+        let (scheme, rest) = target.split_once("://").ok_or(ParseError::InvalidTarget)?;
+        if !scheme.eq_ignore_ascii_case("http") && !scheme.eq_ignore_ascii_case("https") {
+            return Err(ParseError::InvalidTarget);
+        }
+
+        let end = rest.find(['/', '?']).unwrap_or(rest.len());
+        let (authority, origin) = rest.split_at(end);
+        if authority.is_empty() || authority.contains('@') {
+            return Err(ParseError::InvalidTarget);
         }
 
-        let content_length = Self::parse_string_from_header(Header::ContentLength, &headers)
-            .parse::<usize>()
-            .unwrap_or_default();
+        Ok((Some(authority), origin))
+    }
 
-        let method_path: Vec<&str> = headers
-            .first()
-            .ok_or("No request line found in headers")?
-            .split(' ')
-            .collect();
+    fn parse_query(query_string: &str) -> Vec<(String, String)> {
+        serde_urlencoded::from_str(query_string).unwrap_or_default()
+    }
 
-        // TODO: Try figuring out the path with PathBuf::from()
-        let (path, method, query) = Self::parse_method_and_path(method_path).unwrap();
+    // Splits a `name: value` header line. Whitespace between the name and
+    // the colon is not allowed; whitespace around the value is dropped.
+    fn parse_header_line(line: &str) -> Option<(&str, &str)> {
+        let (name, value) = line.split_once(':')?;
 
-        let host = Self::parse_string_from_header(Header::Host, &headers);
-        let user_agent = Self::parse_string_from_header(Header::UserAgent, &headers);
-        let content_type = Self::parse_string_from_header(Header::ContentType, &headers);
-        let accept = Self::parse_string_from_header(Header::Accept, &headers);
-        let accept_encoding = Self::parse_encodings_from_header(Header::AcceptEncoding, &headers);
+        if !header_map::is_valid_name(name) || !header_map::is_valid_value(value) {
+            return None;
+        }
 
-        let mut body_bytes = vec![0u8; content_length];
+        Some((name, value.trim()))
+    }
 
-        let _ = buf_reader.read_exact(&mut body_bytes);
+    // Reads the request line and headers. The body is left in the reader
+    // for `read_body` or `attach_body_stream`, depending on the route.
+    pub fn parse_head(reader: &mut impl BufRead, limits: &Limits) -> Result<Request, ParseError> {
+        let mut request = Request::default();
 
-        let encodings = accept_encoding
-            .iter()
-            .map(|e| e.parse::<EncodingType>().unwrap())
-            .collect::<Vec<_>>();
+        let line_limit = limits.max_uri_length.saturating_add(REQUEST_LINE_OVERHEAD);
+        let line = match Self::read_line(reader, line_limit)? {
+            Line::Eof => return Err(ParseError::ConnectionClosed),
+            Line::TooLong => return Err(ParseError::UriTooLong),
+            Line::Complete(line) => line,
+        };
 
-        request.host = host;
-        request.content_type = content_type;
-        request.accept = accept;
-        request.user_agent = user_agent;
-        request.content_length = content_length;
+        let (method, version, target) = Self::parse_request_line(&line, limits.max_uri_length)?;
+        let (authority, target) = Self::split_target(method, target)?;
+
+        Self::read_headers(reader, &mut request.headers, limits)?;
+
+        // The authority of an absolute-form target replaces any Host header
+        // (RFC 9112 §3.2.2).
+        if let Some(authority) = authority {
+            request.headers.insert(Header::Host, authority);
+        }
+
+        request.framing = if Self::is_chunked(&request.headers)? {
+            Framing::Chunked
+        } else {
+            Framing::Length(Self::parse_content_length(&request.headers)?)
+        };
         request.method = method;
-        request.path = path;
-        request.query = query;
-        request.accept_encoding = encodings;
-        request.body = String::from_utf8(body_bytes).unwrap();
+        request.version = version;
+        let (path, query_string) = target.split_once('?').unwrap_or((target, ""));
+        request.asterisk = target == "*";
+        request.path = path.trim_start_matches('/').to_string();
+        request.query_params = Self::parse_query(query_string);
+        request.query_string = query_string.to_string();
+
+        Ok(request)
+    }
+
+    // Reads a whole request, buffering its body. Anything after it stays in
+    // the reader for the next request on the connection.
+    pub fn parse(reader: &mut impl BufRead, limits: &Limits) -> Result<Request, ParseError> {
+        let mut request = Self::parse_head(reader, limits)?;
+        request.read_body(reader, limits)?;
+
+        Ok(request)
+    }
+
+    // A body announced with a Content-Length over the limit is refused
+    // before any of it is read.
+    pub fn check_body_size(&self, max_body_size: u64) -> Result<(), ParseError> {
+        match self.framing {
+            Framing::Length(length) if length > max_body_size => Err(ParseError::BodyTooLarge),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn read_body(
+        &mut self,
+        reader: &mut impl BufRead,
+        limits: &Limits,
+    ) -> Result<(), ParseError> {
+        self.check_body_size(limits.max_body_size)?;
+
+        let mut stream = BodyStream::new(reader, self.framing, *limits);
+        self.body = Bytes::from(stream.read_to_end()?);
+        self.trailers = stream.into_parts().1;
 
         Ok(())
     }
-}
 
-impl TryFrom<&mut TcpStream> for Request {
-    type Error = ReqError;
+    // Hands the connection to the handler, which reads the body through
+    // `body_reader` instead of it being buffered in `body`.
+    pub(crate) fn attach_body_stream(&mut self, reader: Box<dyn BufRead + Send>, limits: &Limits) {
+        let stream = BodyStream::new(reader, self.framing, *limits);
+
+        self.body_stream = Some(Mutex::new(stream));
+    }
 
-    fn try_from(stream: &mut TcpStream) -> Result<Self, Self::Error> {
-        let mut request = Self::default();
+    pub(crate) fn detach_body_stream(&mut self) -> Option<BodyStream> {
+        self.body_stream
+            .take()
+            .map(|stream| stream.into_inner().unwrap_or_else(PoisonError::into_inner))
+    }
 
-        Self::parse_header_and_body(&mut request, stream).map_err(|_| ReqError {
-            msg: "Something went wrong".to_string(),
-        })?;
+    // The body of a request to a streaming route, read incrementally from
+    // the connection. `None` for routes that buffer their body.
+    pub fn body_reader(&self) -> Option<MutexGuard<'_, BodyStream>> {
+        self.body_stream
+            .as_ref()
+            .map(|stream| stream.lock().unwrap_or_else(PoisonError::into_inner))
+    }
 
-        Ok(request)
+    // Header lines up to the blank line ending the section. Also used for
+    // the trailers of a chunked body.
+    pub(super) fn read_headers(
+        reader: &mut impl BufRead,
+        headers: &mut HeaderMap,
+        limits: &Limits,
+    ) -> Result<(), ParseError> {
+        let mut remaining = limits.max_header_bytes;
+        loop {
+            // The blank line ending the section isn't counted, so there's
+            // always room left to read it.
+            let line = match Self::read_line(reader, remaining.saturating_add(2))? {
+                Line::Eof => return Err(ParseError::UnexpectedEof),
+                Line::TooLong => return Err(ParseError::HeadersTooLarge),
+                Line::Complete(line) => line,
+            };
+            if line.is_empty() {
+                return Ok(());
+            }
+            if headers.len() == limits.max_headers || line.len() + 2 > remaining {
+                return Err(ParseError::HeadersTooLarge);
+            }
+            remaining -= line.len() + 2;
+
+            let (name, value) = Self::parse_header_line(&line).ok_or(ParseError::InvalidHeader)?;
+            headers.append(name, value);
+        }
+    }
+
+    // Only a plain `chunked` coding is supported. Sending Content-Length as
+    // well is rejected outright, even with an empty Transfer-Encoding, since
+    // the two could frame the body differently for us and a proxy in front
+    // of us. Without `chunked` as the final coding the body can't be framed
+    // at all (RFC 9112 §6.3), so that's a bad request rather than an
+    // unsupported one.
+    fn is_chunked(headers: &HeaderMap) -> Result<bool, ParseError> {
+        if !headers.contains(Header::TransferEncoding) {
+            return Ok(false);
+        }
+        if headers.contains(Header::ContentLength) {
+            return Err(ParseError::AmbiguousLength);
+        }
+
+        let codings = headers
+            .get_all(Header::TransferEncoding)
+            .flat_map(|v| v.split(','))
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .collect::<Vec<_>>();
+        let is_chunked = |coding: &&str| coding.eq_ignore_ascii_case("chunked");
+
+        match codings.split_last() {
+            Some((last, others)) if is_chunked(last) && !others.iter().any(is_chunked) => {
+                match others {
+                    [] => Ok(true),
+                    _ => Err(ParseError::UnsupportedTransferEncoding(codings.join(", "))),
+                }
+            }
+            _ => Err(ParseError::InvalidTransferEncoding),
+        }
     }
+
+    // Chunk size line, ignoring any chunk extensions after `;`. The size
+    // itself must be bare hex digits.
+    pub(super) fn read_chunk_size(reader: &mut impl BufRead) -> Result<u64, ParseError> {
+        let line = match Self::read_line(reader, MAX_CHUNK_LINE)? {
+            Line::Eof => return Err(ParseError::UnexpectedEof),
+            Line::TooLong => return Err(ParseError::InvalidChunk),
+            Line::Complete(line) => line,
+        };
+
+        // Whitespace is only allowed before the `;` of an extension.
+        let size = match line.split_once(';') {
+            Some((size, _)) => size.trim_end_matches([' ', '\t']),
+            None => &line,
+        };
+        if size.is_empty() || !size.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(ParseError::InvalidChunk);
+        }
+
+        u64::from_str_radix(size, 16).map_err(|_| ParseError::InvalidChunk)
+    }
+
+    // The line break following the data of a chunk.
+    pub(super) fn read_chunk_end(reader: &mut impl BufRead) -> Result<(), ParseError> {
+        let mut crlf = [0u8; 2];
+        Self::read_exact(reader, &mut crlf)?;
+
+        if crlf != *b"\r\n" {
+            return Err(ParseError::InvalidChunk);
+        }
+
+        Ok(())
+    }
+
+    fn read_exact(reader: &mut impl BufRead, buf: &mut [u8]) -> Result<(), ParseError> {
+        reader.read_exact(buf).map_err(|e| match e.kind() {
+            ErrorKind::UnexpectedEof => ParseError::UnexpectedEof,
+            _ => ParseError::Io(e),
+        })
+    }
+
+    // Repeated Content-Length headers are only accepted when they agree.
+    // Only plain digits are accepted, not the sign `u64::from_str` allows.
+    fn parse_content_length(headers: &HeaderMap) -> Result<u64, ParseError> {
+        let mut lengths = headers.get_all(Header::ContentLength);
+        let Some(first) = lengths.next() else {
+            return Ok(0);
+        };
+
+        if lengths.any(|other| other != first) {
+            return Err(ParseError::InvalidContentLength);
+        }
+
+        if first.is_empty() || !first.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ParseError::InvalidContentLength);
+        }
+
+        first.parse().map_err(|_| ParseError::InvalidContentLength)
+    }
+
+    // Reads a line of at most `limit` bytes including its line ending, which
+    // is stripped. A bare `\n` is accepted as well as `\r\n`.
+    fn read_line(reader: &mut impl BufRead, limit: usize) -> Result<Line, ParseError> {
+        let mut buf = Vec::new();
+        Read::take(&mut *reader, (limit as u64).saturating_add(1)).read_until(b'\n', &mut buf)?;
+
+        if buf.is_empty() {
+            return Ok(Line::Eof);
+        }
+        if buf.last() != Some(&b'\n') {
+            if buf.len() > limit {
+                return Ok(Line::TooLong);
+            }
+            return Err(ParseError::UnexpectedEof);
+        }
+
+        buf.pop();
+        if buf.last() == Some(&b'\r') {
+            buf.pop();
+        }
+
+        String::from_utf8(buf)
+            .map(Line::Complete)
+            .map_err(|_| ParseError::InvalidEncoding)
+    }
+}
+
+enum Line {
+    Eof,
+    TooLong,
+    Complete(String),
 }
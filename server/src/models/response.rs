@@ -1,6 +1,19 @@
-use super::{content_type::ContentType, encoding::EncodingType, status::Status};
+use super::{
+    body::Body,
+    content_type::ContentType,
+    encoding::EncodingType,
+    header_map::{self, HeaderMap, InvalidHeader},
+    headers::Header,
+    method::Method,
+    request::Request,
+    status::Status,
+    version::Version,
+};
 use flate2::{Compression, write::GzEncoder};
-use std::{fmt::Debug, io::Write};
+use std::{
+    fmt::Debug,
+    io::{self, Read, Write},
+};
 
 pub trait IntoResponse<T> {
     fn into_response(self) -> Result<T, Box<dyn std::error::Error>>;
@@ -13,7 +26,8 @@ pub struct Response {
     content_type: ContentType,
     content_length: usize,
     encoding_type: EncodingType,
-    body: Option<Vec<u8>>,
+    headers: HeaderMap,
+    body: Body,
 }
 
 impl From<Response> for Result<Response, Box<dyn std::error::Error>> {
@@ -28,6 +42,46 @@ impl IntoResponse<Response> for Response {
     }
 }
 
+impl<T: IntoResponse<Response>> IntoResponse<Response> for Result<T, Box<dyn std::error::Error>> {
+    fn into_response(self) -> Result<Response, Box<dyn std::error::Error>> {
+        self?.into_response()
+    }
+}
+
+impl IntoResponse<Response> for Status {
+    fn into_response(self) -> Result<Response, Box<dyn std::error::Error>> {
+        Ok(Response::default().status(self))
+    }
+}
+
+impl IntoResponse<Response> for String {
+    fn into_response(self) -> Result<Response, Box<dyn std::error::Error>> {
+        Ok(Response::default().body(self.into_bytes()))
+    }
+}
+
+impl IntoResponse<Response> for &'static str {
+    fn into_response(self) -> Result<Response, Box<dyn std::error::Error>> {
+        Ok(Response::default().body(self.as_bytes().to_vec()))
+    }
+}
+
+impl IntoResponse<Response> for Vec<u8> {
+    fn into_response(self) -> Result<Response, Box<dyn std::error::Error>> {
+        Ok(Response::default()
+            .content_type(ContentType::OctetStream)
+            .body(self))
+    }
+}
+
+impl<T: IntoResponse<Response>> IntoResponse<Response> for (Status, T) {
+    fn into_response(self) -> Result<Response, Box<dyn std::error::Error>> {
+        let (status, res) = self;
+
+        Ok(res.into_response()?.status(status))
+    }
+}
+
 impl Response {
     pub fn from(
         mut body: Option<Vec<u8>>,
@@ -40,7 +94,8 @@ impl Response {
             content_type,
             content_length: body.get_or_insert(Vec::new()).len(),
             encoding_type,
-            body,
+            headers: HeaderMap::new(),
+            body: body.unwrap_or_default().into(),
         }
     }
 
@@ -60,31 +115,158 @@ impl Response {
         self
     }
 
+    // Appends a header, so repeated names such as Set-Cookie are all sent.
+    // A header that can't be sent safely is dropped and logged; use
+    // `try_header` for values that come from the client.
+    pub fn header(mut self, name: impl AsRef<str>, value: impl Into<String>) -> Self {
+        self.headers.append(name, value);
+        self
+    }
+
+    pub fn try_header(
+        mut self,
+        name: impl AsRef<str>,
+        value: impl Into<String>,
+    ) -> Result<Self, InvalidHeader> {
+        self.headers.try_append(name, value)?;
+
+        Ok(self)
+    }
+
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    pub fn headers_mut(&mut self) -> &mut HeaderMap {
+        &mut self.headers
+    }
+
     pub fn body(mut self, body: Vec<u8>) -> Self {
-        self.body = Some(body);
+        self.body = Body::Bytes(body);
 
         self
     }
 
-    pub fn to_bytes(&self) -> Vec<u8> {
-        let body = self.body.as_deref().unwrap_or(&[]);
+    // Streams the body from a reader. With a length it is sent with
+    // Content-Length, without one it is chunk-encoded.
+    pub fn body_reader(mut self, reader: impl Read + Send + 'static, length: Option<u64>) -> Self {
+        self.body = Body::Reader {
+            reader: Box::new(reader),
+            length,
+        };
+
+        self
+    }
+
+    // Streams the body as chunks produced one at a time.
+    pub fn body_chunks<I>(mut self, chunks: I) -> Self
+    where
+        I: IntoIterator<Item = Vec<u8>>,
+        I::IntoIter: Send + 'static,
+    {
+        self.body = Body::Chunks(Box::new(chunks.into_iter()));
+
+        self
+    }
 
-        let headers = format!(
-            "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nContent-Encoding: {}\r\n\r\n",
-            self.status,
-            self.content_type,
-            body.len(),
-            self.encoding_type
-        );
+    // Length of the body as sent, which is nothing for statuses that can't
+    // carry one.
+    pub fn body_length(&self) -> Option<u64> {
+        if self.has_body() {
+            self.body.len()
+        } else {
+            Some(0)
+        }
+    }
 
-        let mut response = Vec::with_capacity(headers.len() + body.len());
+    // 1xx, 204 and 304 responses never have a body or framing headers
+    // (RFC 9110 §8.6, RFC 9112 §6.3).
+    fn has_body(&self) -> bool {
+        !matches!(self.status.as_u16(), 100..=199 | 204 | 304)
+    }
 
-        response.extend_from_slice(headers.as_bytes());
-        response.extend_from_slice(body);
+    // The whole response, for a buffered body. A streamed body can only be
+    // sent once, through `write_to`, so only its head is included.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut response = self.to_head_bytes();
+
+        if self.has_body()
+            && let Some(body) = self.body.as_bytes()
+        {
+            response.extend_from_slice(body);
+        }
 
         response
     }
 
+    // Status line and headers only, as sent in reply to a HEAD request.
+    pub fn to_head_bytes(&self) -> Vec<u8> {
+        self.head_bytes(true)
+    }
+
+    // Sends the response for `req`: only the head for HEAD requests, and a
+    // body of unknown length as raw bytes for HTTP/1.0 clients, which can't
+    // decode chunks. Those rely on the connection being closed afterwards.
+    pub fn write_to(self, out: &mut impl Write, req: &Request) -> io::Result<()> {
+        let chunked = req.version != Version::Http10;
+
+        out.write_all(&self.head_bytes(chunked))?;
+        if req.method != Method::Head && self.has_body() {
+            // Let the client see the head before a streamed body is ready.
+            if self.body.is_streamed() {
+                out.flush()?;
+            }
+            self.body.write_to(out, chunked)?;
+        }
+
+        out.flush()
+    }
+
+    fn head_bytes(&self, chunked: bool) -> Vec<u8> {
+        let mut headers = format!("HTTP/1.1 {}\r\n", self.status);
+
+        let has_body = self.has_body();
+        match self.body.len() {
+            _ if !has_body => {}
+            Some(length) => headers.push_str(&format!("Content-Length: {}\r\n", length)),
+            None if chunked => headers.push_str("Transfer-Encoding: chunked\r\n"),
+            None => {}
+        }
+
+        // Framing headers always reflect the body, while the typed
+        // Content-Type and Content-Encoding give way to explicit headers
+        // and are left out when there's no body to describe.
+        let typed = if has_body {
+            vec![
+                (Header::ContentType, self.content_type.to_string()),
+                (Header::ContentEncoding, self.encoding_type.to_string()),
+            ]
+        } else {
+            Vec::new()
+        };
+
+        for (name, value) in &typed {
+            if !self.headers.contains(name) && !value.is_empty() {
+                let name = header_map::canonical_name(name.as_ref());
+                headers.push_str(&format!("{}: {}\r\n", name, value));
+            }
+        }
+
+        let framing = [
+            Header::ContentLength.as_ref(),
+            Header::TransferEncoding.as_ref(),
+        ];
+        for (name, value) in self.headers.iter() {
+            if !framing.contains(&name) && !value.is_empty() {
+                let name = header_map::canonical_name(name);
+                headers.push_str(&format!("{}: {}\r\n", name, value));
+            }
+        }
+        headers.push_str("\r\n");
+
+        headers.into_bytes()
+    }
+
     pub fn encode_payload<T>(payload: T, encoding_type: &EncodingType) -> Vec<u8>
     where
         T: Debug + Into<Vec<u8>>,
@@ -1,14 +1,141 @@
-use strum::Display;
+use std::fmt;
+
+macro_rules! statuses {
+    ($($(#[$attr:meta])* $variant:ident = $code:literal, $reason:literal;)*) => {
+        #[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+        pub enum Status {
+            $($(#[$attr])* $variant,)*
+            // Non-standard code with its own reason phrase, made through
+            // `Status::custom`.
+            Custom(CustomStatus),
+        }
+
+        impl Status {
+            pub fn from_u16(code: u16) -> Option<Self> {
+                match code {
+                    $($code => Some(Status::$variant),)*
+                    _ => None,
+                }
+            }
+
+            pub fn as_u16(&self) -> u16 {
+                match self {
+                    $(Status::$variant => $code,)*
+                    Status::Custom(custom) => custom.code,
+                }
+            }
+
+            pub fn reason(&self) -> &'static str {
+                match self {
+                    $(Status::$variant => $reason,)*
+                    Status::Custom(custom) => custom.reason,
+                }
+            }
+        }
+    };
+}
+
+// RFC 9110 section 15, plus 428, 429 and 431 from RFC 6585.
+statuses! {
+    Continue = 100, "Continue";
+    SwitchingProtocols = 101, "Switching Protocols";
 
-#[derive(Debug, Copy, Clone, Default, Display)]
-pub enum Status {
     #[default]
-    #[strum(to_string = "200 OK")]
-    Ok = 200,
-    #[strum(to_string = "201 Created")]
-    Created = 201,
-    #[strum(to_string = "202 Accepted")]
-    Accepted = 202,
-    #[strum(to_string = "404 Not Found")]
-    NotFound = 404,
+    Ok = 200, "OK";
+    Created = 201, "Created";
+    Accepted = 202, "Accepted";
+    NonAuthoritativeInformation = 203, "Non-Authoritative Information";
+    NoContent = 204, "No Content";
+    ResetContent = 205, "Reset Content";
+    PartialContent = 206, "Partial Content";
+
+    MultipleChoices = 300, "Multiple Choices";
+    MovedPermanently = 301, "Moved Permanently";
+    Found = 302, "Found";
+    SeeOther = 303, "See Other";
+    NotModified = 304, "Not Modified";
+    UseProxy = 305, "Use Proxy";
+    TemporaryRedirect = 307, "Temporary Redirect";
+    PermanentRedirect = 308, "Permanent Redirect";
+
+    BadRequest = 400, "Bad Request";
+    Unauthorized = 401, "Unauthorized";
+    PaymentRequired = 402, "Payment Required";
+    Forbidden = 403, "Forbidden";
+    NotFound = 404, "Not Found";
+    MethodNotAllowed = 405, "Method Not Allowed";
+    NotAcceptable = 406, "Not Acceptable";
+    ProxyAuthenticationRequired = 407, "Proxy Authentication Required";
+    RequestTimeout = 408, "Request Timeout";
+    Conflict = 409, "Conflict";
+    Gone = 410, "Gone";
+    LengthRequired = 411, "Length Required";
+    PreconditionFailed = 412, "Precondition Failed";
+    ContentTooLarge = 413, "Content Too Large";
+    UriTooLong = 414, "URI Too Long";
+    UnsupportedMediaType = 415, "Unsupported Media Type";
+    RangeNotSatisfiable = 416, "Range Not Satisfiable";
+    ExpectationFailed = 417, "Expectation Failed";
+    MisdirectedRequest = 421, "Misdirected Request";
+    UnprocessableContent = 422, "Unprocessable Content";
+    UpgradeRequired = 426, "Upgrade Required";
+    PreconditionRequired = 428, "Precondition Required";
+    TooManyRequests = 429, "Too Many Requests";
+    RequestHeaderFieldsTooLarge = 431, "Request Header Fields Too Large";
+
+    InternalServerError = 500, "Internal Server Error";
+    NotImplemented = 501, "Not Implemented";
+    BadGateway = 502, "Bad Gateway";
+    ServiceUnavailable = 503, "Service Unavailable";
+    GatewayTimeout = 504, "Gateway Timeout";
+    HttpVersionNotSupported = 505, "HTTP Version Not Supported";
+}
+
+// Fields are private so every custom status goes through the checks in
+// `Status::custom` before it reaches the status line.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct CustomStatus {
+    code: u16,
+    reason: &'static str,
+}
+
+impl Status {
+    // A status outside the standard set. The code must have three digits
+    // and the reason phrase can't contain control characters other than
+    // tab, which would end the status line early. A standard code gives
+    // its usual variant, so it compares equal to it.
+    pub fn custom(code: u16, reason: &'static str) -> Option<Self> {
+        let valid_reason = reason.chars().all(|c| c == '\t' || !c.is_control());
+        if !(100..=999).contains(&code) || !valid_reason {
+            return None;
+        }
+
+        Some(Status::from_u16(code).unwrap_or(Status::Custom(CustomStatus { code, reason })))
+    }
+
+    pub fn is_informational(&self) -> bool {
+        (100..200).contains(&self.as_u16())
+    }
+
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.as_u16())
+    }
+
+    pub fn is_redirection(&self) -> bool {
+        (300..400).contains(&self.as_u16())
+    }
+
+    pub fn is_client_error(&self) -> bool {
+        (400..500).contains(&self.as_u16())
+    }
+
+    pub fn is_server_error(&self) -> bool {
+        (500..600).contains(&self.as_u16())
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.as_u16(), self.reason())
+    }
 }
@@ -0,0 +1,12 @@
+use strum::{Display, EnumString};
+
+#[derive(
+    Debug, Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd, Default, Display, EnumString,
+)]
+pub enum Version {
+    #[strum(serialize = "HTTP/1.0")]
+    Http10,
+    #[default]
+    #[strum(serialize = "HTTP/1.1")]
+    Http11,
+}
@@ -1,94 +1,109 @@
-use std::collections::HashMap;
+use std::sync::Arc;
 
 use crate::{
-    app::{MethodHandlerMap, RequestHandler},
+    app::{Endpoint, Handler},
+    middleware::{Layer, Middleware},
     models::method::Method,
 };
 
+pub mod pattern;
+pub mod tree;
+
 pub struct Router {
     pub base: String,
-    pub routes: HashMap<String, MethodHandlerMap>,
+    pub routes: Vec<(String, Method, Endpoint)>,
     pub sub_routers: Vec<Router>,
+    pub layers: Vec<Layer>,
 }
 
 impl Router {
     pub fn new(base: impl Into<String>) -> Self {
         Self {
             base: base.into(),
-            routes: HashMap::new(),
+            routes: Vec::new(),
             sub_routers: Vec::new(),
+            layers: Vec::new(),
         }
     }
 
+    // Applies to every route of this router and its sub-routers, no matter
+    // whether they were added before or after the middleware.
+    pub fn layer(mut self, middleware: impl Middleware) -> Self {
+        self.layers.push(Arc::new(middleware));
+
+        self
+    }
+
     pub fn route(mut self, sub: Router) -> Self {
         self.sub_routers.push(sub);
 
         self
     }
 
-    pub fn get(mut self, path: &str, handler: RequestHandler) -> Self {
+    pub fn get<Args>(mut self, path: &str, handler: impl Handler<Args>) -> Self {
         self.add(Method::Get, path, handler);
 
         self
     }
 
-    pub fn post(mut self, path: &str, handler: RequestHandler) -> Self {
+    pub fn post<Args>(mut self, path: &str, handler: impl Handler<Args>) -> Self {
         self.add(Method::Post, path, handler);
 
         self
     }
 
-    pub fn put(mut self, path: &str, handler: RequestHandler) -> Self {
+    pub fn put<Args>(mut self, path: &str, handler: impl Handler<Args>) -> Self {
         self.add(Method::Put, path, handler);
 
         self
     }
 
-    pub fn patch(mut self, path: &str, handler: RequestHandler) -> Self {
+    pub fn patch<Args>(mut self, path: &str, handler: impl Handler<Args>) -> Self {
         self.add(Method::Patch, path, handler);
 
         self
     }
 
-    pub fn delete(mut self, path: &str, handler: RequestHandler) -> Self {
+    pub fn delete<Args>(mut self, path: &str, handler: impl Handler<Args>) -> Self {
         self.add(Method::Delete, path, handler);
 
         self
     }
 
-    pub fn add(&mut self, method: Method, path: &str, handler: RequestHandler) {
-        let full_path = match path.trim() {
-            "" => self.base.trim_matches('/').to_string(),
-            path => format!(
-                "{}/{}",
-                self.base.trim_end_matches("/"),
-                path.trim_start_matches("/")
-            ),
-        };
-
-        let entry = self.routes.entry(full_path).or_default();
-        entry.insert(method, handler);
+    pub fn head<Args>(mut self, path: &str, handler: impl Handler<Args>) -> Self {
+        self.add(Method::Head, path, handler);
+
+        self
     }
 
-    pub fn into_routes(self) -> HashMap<String, MethodHandlerMap> {
+    pub fn options<Args>(mut self, path: &str, handler: impl Handler<Args>) -> Self {
+        self.add(Method::Options, path, handler);
+
+        self
+    }
+
+    pub fn add<Args>(&mut self, method: Method, path: &str, handler: impl Handler<Args>) {
+        let full_path = pattern::normalize(&format!("{}/{}", self.base, path.trim()));
+        let endpoint = handler.into_endpoint();
+
+        self.routes.push((full_path, method, endpoint));
+    }
+
+    pub fn into_routes(self) -> Vec<(String, Method, Endpoint)> {
         let mut all_routes = self.routes;
 
         for sub in self.sub_routers {
-            let nested = sub.into_routes();
-
-            for (sub_path, methods) in nested {
-                let full_path = format!(
-                    "{}/{}",
-                    self.base.trim_end_matches("/"),
-                    sub_path.trim_start_matches("/")
-                )
-                .trim_end_matches("/")
-                .to_string();
-
-                all_routes.entry(full_path).or_default().extend(methods);
+            for (sub_path, method, endpoint) in sub.into_routes() {
+                let full_path = pattern::normalize(&format!("{}/{}", self.base, sub_path));
+
+                all_routes.push((full_path, method, endpoint));
             }
         }
 
+        for (_, _, endpoint) in &mut all_routes {
+            endpoint.layers.splice(0..0, self.layers.iter().cloned());
+        }
+
         all_routes
     }
 }
@@ -0,0 +1,68 @@
+use std::collections::HashMap;
+
+pub type Params = HashMap<String, String>;
+
+// Route patterns are made of `/`-separated segments:
+//   `:name`  captures exactly one segment
+//   `*`      matches exactly one segment without capturing it
+//   `*name`  captures one or more trailing segments, joined with `/`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Segment<'a> {
+    Static(&'a str),
+    Param(&'a str),
+    Wildcard,
+    CatchAll(&'a str),
+}
+
+impl<'a> Segment<'a> {
+    pub fn parse(segment: &'a str) -> Self {
+        match segment {
+            "*" => Segment::Wildcard,
+            s if s.starts_with('*') => Segment::CatchAll(&s[1..]),
+            s if s.starts_with(':') => Segment::Param(&s[1..]),
+            s => Segment::Static(s),
+        }
+    }
+}
+
+pub fn normalize(route: &str) -> String {
+    segments(route).collect::<Vec<_>>().join("/")
+}
+
+pub fn parse(route: &str) -> impl Iterator<Item = Segment<'_>> {
+    segments(route).map(Segment::parse)
+}
+
+pub fn segments(path: &str) -> impl Iterator<Item = &str> {
+    path.split('/').filter(|s| !s.is_empty())
+}
+
+// Splits a request path into segments and decodes each one, so an escaped
+// `/` stays inside its segment. `None` if any of them is malformed.
+pub fn decode_segments(path: &str) -> Option<Vec<String>> {
+    segments(path).map(percent_decode).collect()
+}
+
+// Decodes `%XX` escapes in a path segment. Unlike in a query
+// string, `+` is left alone. `None` for a malformed escape or a result that
+// isn't UTF-8.
+pub fn percent_decode(segment: &str) -> Option<String> {
+    let mut bytes = segment.bytes();
+    let mut decoded = Vec::with_capacity(segment.len());
+
+    while let Some(b) = bytes.next() {
+        if b != b'%' {
+            decoded.push(b);
+            continue;
+        }
+
+        let (hi, lo) = (bytes.next()?, bytes.next()?);
+        if !hi.is_ascii_hexdigit() || !lo.is_ascii_hexdigit() {
+            return None;
+        }
+        let hex = [hi, lo];
+        decoded.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+    }
+
+    String::from_utf8(decoded).ok()
+}
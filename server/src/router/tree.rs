@@ -0,0 +1,178 @@
+use std::{collections::HashMap, fmt};
+
+use thiserror::Error;
+
+use crate::{
+    app::{Endpoint, MethodHandlerMap},
+    models::method::Method,
+};
+
+use super::pattern::{self, Params, Segment};
+
+#[derive(Debug, Error)]
+pub enum RouteError {
+    #[error("route `{route}` is registered more than once for {method:?}")]
+    Duplicate { method: Method, route: String },
+    #[error("route `{route}` is ambiguous with `{existing}`")]
+    Conflict { route: String, existing: String },
+    #[error("invalid route `{route}`: {reason}")]
+    Invalid { route: String, reason: &'static str },
+}
+
+// Prefix tree keyed by path segment. Lookups walk one node per segment and
+// try static children first, then a parameter or wildcard, then a catch-all.
+#[derive(Default)]
+pub struct RouteTree {
+    root: Node,
+}
+
+#[derive(Default)]
+struct Node {
+    route: String,
+    handlers: MethodHandlerMap,
+    statics: HashMap<String, Node>,
+    param: Option<Box<Dynamic>>,
+    catch_all: Option<Box<Dynamic>>,
+}
+
+struct Dynamic {
+    name: Option<String>,
+    route: String,
+    node: Node,
+}
+
+impl RouteTree {
+    pub fn insert(
+        &mut self,
+        method: Method,
+        route: &str,
+        endpoint: Endpoint,
+    ) -> Result<(), RouteError> {
+        let route = pattern::normalize(route);
+        let segments = pattern::parse(&route).collect::<Vec<_>>();
+
+        let mut node = &mut self.root;
+        for (i, segment) in segments.iter().enumerate() {
+            node = match *segment {
+                Segment::Static(s) => node.statics.entry(s.to_string()).or_default(),
+                Segment::Param("") => {
+                    return Err(RouteError::Invalid {
+                        route,
+                        reason: "parameters must be named",
+                    });
+                }
+                Segment::Param(name) => Dynamic::enter(&mut node.param, Some(name), &route)?,
+                Segment::Wildcard => Dynamic::enter(&mut node.param, None, &route)?,
+                Segment::CatchAll(_) if i + 1 != segments.len() => {
+                    return Err(RouteError::Invalid {
+                        route,
+                        reason: "catch-all segments must come last",
+                    });
+                }
+                Segment::CatchAll(name) => Dynamic::enter(&mut node.catch_all, Some(name), &route)?,
+            };
+        }
+
+        if node.handlers.contains_key(&method) {
+            return Err(RouteError::Duplicate { method, route });
+        }
+
+        node.route = route;
+        node.handlers.insert(method, endpoint);
+
+        Ok(())
+    }
+
+    // Looks up a raw request path. Paths with a malformed escape match
+    // nothing.
+    pub fn find(&self, path: &str) -> Option<(&MethodHandlerMap, Params)> {
+        self.find_segments(&pattern::decode_segments(path)?)
+    }
+
+    // Looks up a path already split into percent-decoded segments.
+    pub fn find_segments(&self, segments: &[String]) -> Option<(&MethodHandlerMap, Params)> {
+        let mut params = Params::new();
+
+        self.root
+            .find(segments, &mut params)
+            .map(|handlers| (handlers, params))
+    }
+}
+
+impl Node {
+    fn find(&self, segments: &[String], params: &mut Params) -> Option<&MethodHandlerMap> {
+        let Some((first, rest)) = segments.split_first() else {
+            return (!self.handlers.is_empty()).then_some(&self.handlers);
+        };
+
+        if let Some(found) = self.statics.get(first).and_then(|n| n.find(rest, params)) {
+            return Some(found);
+        }
+
+        if let Some(dynamic) = &self.param
+            && let Some(found) = dynamic.node.find(rest, params)
+        {
+            if let Some(name) = &dynamic.name {
+                params.insert(name.clone(), first.clone());
+            }
+            return Some(found);
+        }
+
+        if let Some(dynamic) = &self.catch_all
+            && !dynamic.node.handlers.is_empty()
+        {
+            if let Some(name) = &dynamic.name {
+                params.insert(name.clone(), segments.join("/"));
+            }
+            return Some(&dynamic.node.handlers);
+        }
+
+        None
+    }
+
+    fn collect<'a>(&'a self, routes: &mut Vec<(&'a str, Vec<&'a Method>)>) {
+        if !self.handlers.is_empty() {
+            routes.push((&self.route, self.handlers.keys().collect()));
+        }
+
+        let dynamic = self.param.iter().chain(self.catch_all.iter());
+        for node in self.statics.values().chain(dynamic.map(|d| &d.node)) {
+            node.collect(routes);
+        }
+    }
+}
+
+impl Dynamic {
+    fn enter<'a>(
+        slot: &'a mut Option<Box<Dynamic>>,
+        name: Option<&str>,
+        route: &str,
+    ) -> Result<&'a mut Node, RouteError> {
+        let dynamic = slot.get_or_insert_with(|| {
+            Box::new(Dynamic {
+                name: name.map(ToString::to_string),
+                route: route.to_string(),
+                node: Node::default(),
+            })
+        });
+
+        if dynamic.name.as_deref() != name {
+            return Err(RouteError::Conflict {
+                route: route.to_string(),
+                existing: dynamic.route.clone(),
+            });
+        }
+
+        Ok(&mut dynamic.node)
+    }
+}
+
+impl fmt::Debug for RouteTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut routes = Vec::new();
+        self.root.collect(&mut routes);
+        routes.sort_by_key(|(route, _)| *route);
+
+        f.debug_map().entries(routes).finish()
+    }
+}
@@ -1,8 +1,13 @@
 use std::{
-    sync::{Arc, Mutex, mpsc},
+    any::Any,
+    io,
+    panic::{self, AssertUnwindSafe},
+    sync::{Arc, Mutex, PoisonError, mpsc},
     thread,
 };
 
+use thiserror::Error;
+
 #[derive(Debug)]
 pub struct ThreadPool {
     workers: Vec<Worker>,
@@ -11,29 +16,88 @@ pub struct ThreadPool {
 
 type Job = Box<dyn FnOnce() + Send + 'static>;
 
-pub struct PoolCreationError;
+#[derive(Debug, Error)]
+pub enum PoolCreationError {
+    #[error("a thread pool needs at least one thread")]
+    ZeroSize,
+    #[error("couldn't spawn a worker thread: {0}")]
+    Spawn(#[from] io::Error),
+}
 
-impl ThreadPool {
+// Options for the worker threads. Threads are named `<name>-<id>`, which
+// shows up in panic messages and debuggers.
+#[derive(Debug, Clone)]
+pub struct ThreadPoolBuilder {
+    size: usize,
+    name: String,
+    stack_size: Option<usize>,
+}
+
+impl ThreadPoolBuilder {
     pub fn new(size: usize) -> Self {
-        assert!(size > 0);
+        Self {
+            size,
+            name: String::from("worker"),
+            stack_size: None,
+        }
+    }
 
-        let (sender, receiver) = mpsc::channel();
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
 
-        let receiver = Arc::new(Mutex::new(receiver));
+        self
+    }
+
+    pub fn stack_size(mut self, bytes: usize) -> Self {
+        self.stack_size = Some(bytes);
+
+        self
+    }
 
-        let mut workers = Vec::with_capacity(size);
-        for id in 0..size {
-            workers.push(Worker::new(id, Arc::clone(&receiver)))
+    pub fn build(self) -> Result<ThreadPool, PoolCreationError> {
+        if self.size == 0 {
+            return Err(PoolCreationError::ZeroSize);
         }
 
-        Self {
-            workers,
+        let (sender, receiver) = mpsc::channel();
+
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        // If a spawn fails, dropping the pool stops the workers already
+        // running.
+        let mut pool = ThreadPool {
+            workers: Vec::with_capacity(self.size),
             sender: Some(sender),
+        };
+        for id in 0..self.size {
+            let mut builder = thread::Builder::new().name(format!("{}-{}", self.name, id));
+            if let Some(stack_size) = self.stack_size {
+                builder = builder.stack_size(stack_size);
+            }
+
+            pool.workers
+                .push(Worker::new(id, builder, Arc::clone(&receiver))?);
         }
+
+        Ok(pool)
+    }
+}
+
+impl ThreadPool {
+    pub fn new(size: usize) -> Self {
+        Self::build(size).expect("Couldn't create thread pool")
+    }
+
+    pub fn build(size: usize) -> Result<ThreadPool, PoolCreationError> {
+        ThreadPoolBuilder::new(size).build()
     }
 
-    pub fn build(_size: usize) -> Result<ThreadPool, PoolCreationError> {
-        todo!()
+    pub fn builder(size: usize) -> ThreadPoolBuilder {
+        ThreadPoolBuilder::new(size)
+    }
+
+    pub fn size(&self) -> usize {
+        self.workers.len()
     }
 
     pub fn execute<F>(&self, f: F)
@@ -64,15 +128,26 @@ struct Worker {
 }
 
 impl Worker {
-    fn new(id: usize, receiver: Arc<Mutex<mpsc::Receiver<Job>>>) -> Self {
-        // For prod env, use thread::Builder::spawn instead of thread::spawn
-        let thread = thread::spawn(move || {
+    fn new(
+        id: usize,
+        builder: thread::Builder,
+        receiver: Arc<Mutex<mpsc::Receiver<Job>>>,
+    ) -> io::Result<Self> {
+        let thread = builder.spawn(move || {
             loop {
-                let message = receiver.lock().unwrap().recv();
+                // The lock is never held while a job runs, so a poisoned
+                // mutex still guards a usable receiver.
+                let message = receiver
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .recv();
                 match message {
                     Ok(job) => {
                         println!("Worker {id} got a job; executing.");
-                        job();
+                        // A panicking job must not take the worker down with it.
+                        if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(job)) {
+                            eprintln!("Worker {id} job panicked: {}", panic_message(&*payload));
+                        }
                     }
                     Err(_) => {
                         println!("Worker {id} disconnected; shutting down");
@@ -80,8 +155,16 @@ impl Worker {
                     }
                 }
             }
-        });
+        })?;
 
-        Self { id, thread }
+        Ok(Self { id, thread })
     }
 }
+
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("non-string panic payload")
+}
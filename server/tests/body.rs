@@ -0,0 +1,66 @@
+mod test_utils;
+
+#[cfg(test)]
+mod tests {
+
+    use std::{sync::Arc, thread};
+
+    use reqwest::blocking::Client;
+    use server::{
+        app::{App, ServerResponse},
+        models::{request::Request, response::Response, status::Status},
+    };
+
+    use crate::test_utils::{local_listener, wait_until_server_ready};
+
+    const BINARY: &[u8] = &[0x1f, 0x8b, 0x08, 0x00, 0xff, 0xfe, 0x00, 0xc3, 0x28];
+
+    #[test]
+    fn binary_bodies_are_preserved() {
+        let app = App::from_listener(local_listener())
+            .unwrap()
+            .post("raw", raw)
+            .post("text", text)
+            .build()
+            .unwrap();
+        let addr = app.local_addr().unwrap().to_string();
+
+        let server = Arc::clone(&app);
+        let handle = thread::spawn(move || server.run());
+
+        wait_until_server_ready(&addr);
+
+        let client = Client::new();
+        let post = |path: &str, body: &'static [u8]| {
+            client
+                .post(format!("http://{}{}", addr, path))
+                .body(body)
+                .send()
+                .expect("Couldn't send request to the server")
+        };
+
+        let res = post("/raw", BINARY);
+        assert_eq!(res.status(), 200);
+        assert_eq!(res.bytes().unwrap().as_ref(), BINARY);
+
+        let res = post("/text", "héllo".as_bytes());
+        assert_eq!(res.text().unwrap(), "HÉLLO");
+
+        let res = post("/text", BINARY);
+        assert_eq!(res.status(), 400);
+
+        app.shutdown();
+        handle.join().unwrap();
+    }
+
+    fn raw(req: &Request, res: Response) -> ServerResponse {
+        res.body(req.body.to_vec()).into()
+    }
+
+    fn text(req: &Request, res: Response) -> ServerResponse {
+        match req.text() {
+            Ok(text) => res.body(text.to_uppercase().into_bytes()).into(),
+            Err(_) => res.status(Status::BadRequest).into(),
+        }
+    }
+}
@@ -0,0 +1,133 @@
+mod test_utils;
+
+#[cfg(test)]
+mod tests {
+
+    use std::{sync::Arc, thread};
+
+    use server::{
+        app::{App, ServerResponse},
+        models::{request::Request, response::Response},
+    };
+
+    use crate::test_utils::{local_listener, send, wait_until_server_ready};
+
+    #[test]
+    fn chunked_bodies_are_decoded() {
+        let app = App::from_listener(local_listener())
+            .unwrap()
+            .post("upload", upload)
+            .build()
+            .unwrap();
+        let addr = app.local_addr().unwrap().to_string();
+
+        let server = Arc::clone(&app);
+        let handle = thread::spawn(move || server.run());
+
+        wait_until_server_ready(&addr);
+
+        // A chunked upload followed by a pipelined request on the same
+        // connection: the second one must not be read as part of the body.
+        let response = send(
+            &addr,
+            b"POST /upload HTTP/1.1\r\n\
+              Transfer-Encoding: chunked\r\n\r\n\
+              5\r\nhello\r\n\
+              7 \t;ext=1\r\n, world\r\n\
+              0\r\n\
+              X-Checksum: abc\r\n\r\n\
+              POST /upload HTTP/1.1\r\nConnection: close\r\nContent-Length: 4\r\n\r\nnext",
+        );
+
+        let mut responses = response.split("HTTP/1.1 ").skip(1);
+        let first = responses.next().unwrap();
+        assert!(first.starts_with("200 OK"));
+        assert!(first.ends_with("hello, world|abc"));
+        let second = responses.next().unwrap();
+        assert!(second.ends_with("next|"));
+
+        app.shutdown();
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn ambiguous_or_broken_framing_is_rejected() {
+        let app = App::from_listener(local_listener())
+            .unwrap()
+            .post("upload", upload)
+            .build()
+            .unwrap();
+        let addr = app.local_addr().unwrap().to_string();
+
+        let server = Arc::clone(&app);
+        let handle = thread::spawn(move || server.run());
+
+        wait_until_server_ready(&addr);
+
+        let cases: [(&[u8], &str); 10] = [
+            (
+                b"POST /upload HTTP/1.1\r\nContent-Length: 5\r\n\
+                  Transfer-Encoding: chunked\r\n\r\n0\r\n\r\n",
+                "HTTP/1.1 400 Bad Request",
+            ),
+            (
+                b"POST /upload HTTP/1.1\r\nContent-Length: 5\r\n\
+                  Transfer-Encoding:\r\n\r\nhello",
+                "HTTP/1.1 400 Bad Request",
+            ),
+            (
+                b"POST /upload HTTP/1.1\r\nTransfer-Encoding:\r\n\r\n",
+                "HTTP/1.1 400 Bad Request",
+            ),
+            (
+                b"POST /upload HTTP/1.1\r\nTransfer-Encoding: gzip, chunked\r\n\r\n0\r\n\r\n",
+                "HTTP/1.1 501 Not Implemented",
+            ),
+            (
+                b"POST /upload HTTP/1.1\r\nTransfer-Encoding: chunked, gzip\r\n\r\n0\r\n\r\n",
+                "HTTP/1.1 400 Bad Request",
+            ),
+            (
+                b"POST /upload HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\
+                  Transfer-Encoding: chunked\r\n\r\n0\r\n\r\n",
+                "HTTP/1.1 400 Bad Request",
+            ),
+            (
+                b"POST /upload HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\nzz\r\n\r\n",
+                "HTTP/1.1 400 Bad Request",
+            ),
+            (
+                b"POST /upload HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n 3 \r\nabc\r\n0\r\n\r\n",
+                "HTTP/1.1 400 Bad Request",
+            ),
+            (
+                b"POST /upload HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n3 \r\nabc\r\n0\r\n\r\n",
+                "HTTP/1.1 400 Bad Request",
+            ),
+            (
+                b"POST /upload HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n3\r\nabcdef\r\n0\r\n\r\n",
+                "HTTP/1.1 400 Bad Request",
+            ),
+        ];
+
+        for (raw, expected) in cases {
+            let response = send(&addr, raw);
+            assert!(
+                response.starts_with(expected),
+                "expected `{}`, got `{}`",
+                expected,
+                response
+            );
+        }
+
+        app.shutdown();
+        handle.join().unwrap();
+    }
+
+    fn upload(req: &Request, res: Response) -> ServerResponse {
+        let checksum = req.trailers.get("x-checksum").unwrap_or_default();
+
+        res.body(format!("{}|{}", req.text()?, checksum).into_bytes())
+            .into()
+    }
+}
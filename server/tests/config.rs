@@ -0,0 +1,70 @@
+mod test_utils;
+
+#[cfg(test)]
+mod tests {
+
+    use std::{net::TcpListener, sync::Arc, thread, time::Duration};
+
+    use server::{
+        app::{
+            App, ServerResponse,
+            config::{ServerConfig, ServerError},
+        },
+        models::{request::Request, response::Response},
+        thread_pool::PoolCreationError,
+    };
+
+    use crate::test_utils::{local_listener, wait_until_server_ready};
+
+    #[test]
+    fn bind_failures_are_errors() {
+        let taken = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = taken.local_addr().unwrap();
+
+        assert!(matches!(App::bind(addr), Err(ServerError::Bind(_))));
+        assert!(matches!(
+            App::builder().bind("not an address"),
+            Err(ServerError::Bind(_))
+        ));
+        assert!(matches!(
+            App::builder().workers(0).listener(local_listener()),
+            Err(ServerError::Pool(PoolCreationError::ZeroSize))
+        ));
+    }
+
+    #[test]
+    fn app_serves_on_a_prebound_listener() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+
+        let config = ServerConfig {
+            workers: 2,
+            keep_alive: Duration::from_secs(1),
+            encodings: Vec::new(),
+            ..ServerConfig::default()
+        };
+        let app = App::builder()
+            .config(config)
+            .listener(listener)
+            .unwrap()
+            .get("", root)
+            .build()
+            .unwrap();
+        let addr = app.local_addr().unwrap().to_string();
+
+        let server = Arc::clone(&app);
+        let handle = thread::spawn(move || server.run());
+
+        wait_until_server_ready(&addr);
+
+        let res = reqwest::blocking::get(format!("http://{}/", addr)).unwrap();
+        assert_eq!(res.status(), 200);
+        assert_eq!(res.text().unwrap(), "served");
+
+        app.shutdown();
+        handle.join().unwrap();
+    }
+
+    fn root(_req: &Request, res: Response) -> ServerResponse {
+        res.body(b"served".to_vec()).into()
+    }
+}
@@ -16,7 +16,10 @@ mod tests {
 
     #[test]
     fn echo() {
-        let app = App::new(BASE_URL).get("echo", echo_handler).build();
+        let app = App::new(BASE_URL)
+            .get("echo/:message", echo_handler)
+            .build()
+            .unwrap();
 
         let server = Arc::clone(&app);
         let handle = thread::spawn(move || server.run());
@@ -34,14 +37,9 @@ mod tests {
     }
 
     fn echo_handler(req: &Request, res: Response) -> ServerResponse {
-        let req_path: Vec<&str> = req.path.split("/").collect();
+        let response_body = req.param("message").unwrap_or_default().to_string();
 
-        let response_body = match req_path.last() {
-            Some(s) => String::from(*s),
-            None => String::new(),
-        };
-
-        let encoding = if req.accept_encoding.contains(&EncodingType::Gzip) {
+        let encoding = if req.accept_encoding().contains(&EncodingType::Gzip) {
             EncodingType::Gzip
         } else {
             EncodingType::None
@@ -0,0 +1,119 @@
+mod test_utils;
+
+#[cfg(test)]
+mod tests {
+
+    use std::{sync::Arc, thread};
+
+    use reqwest::blocking::Client;
+    use serde::{Deserialize, Serialize};
+    use server::{
+        app::App,
+        extract::{Json, Path, Query, State},
+        models::status::Status,
+    };
+
+    use crate::test_utils::{local_listener, wait_until_server_ready};
+
+    #[derive(Deserialize)]
+    struct UserId(u32);
+
+    #[derive(Deserialize)]
+    struct PostPath {
+        id: u32,
+        post_id: String,
+    }
+
+    #[derive(Deserialize)]
+    struct Filters {
+        tag: String,
+        limit: Option<usize>,
+    }
+
+    #[derive(Deserialize, Serialize)]
+    struct NewUser {
+        name: String,
+        age: u8,
+    }
+
+    struct Prefix(&'static str);
+
+    fn user(Path(UserId(id)): Path<UserId>, Query(filters): Query<Filters>) -> String {
+        format!("{}:{}:{}", id, filters.tag, filters.limit.unwrap_or(10))
+    }
+
+    fn post(Path(path): Path<PostPath>) -> String {
+        format!("{}/{}", path.id, path.post_id)
+    }
+
+    fn create(State(prefix): State<Prefix>, Json(user): Json<NewUser>) -> (Status, Json<NewUser>) {
+        let name = format!("{}{}", prefix.0, user.name);
+
+        (Status::Created, Json(NewUser { name, ..user }))
+    }
+
+    #[test]
+    fn extractors_parse_request_parts() {
+        let app = App::from_listener(local_listener())
+            .unwrap()
+            .with_state(Prefix("new-"))
+            .get("users/:id", user)
+            .get("users/:id/posts/:post_id", post)
+            .post("users", create)
+            .build()
+            .unwrap();
+        let addr = app.local_addr().unwrap().to_string();
+
+        let server = Arc::clone(&app);
+        let handle = thread::spawn(move || server.run());
+
+        wait_until_server_ready(&addr);
+
+        let client = Client::new();
+        let get = |path: &str| {
+            let res = client
+                .get(format!("http://{}{}", addr, path))
+                .send()
+                .expect("Couldn't send request to the server");
+            (res.status().as_u16(), res.text().unwrap())
+        };
+
+        assert_eq!(get("/users/7?tag=rust"), (200, String::from("7:rust:10")));
+        assert_eq!(get("/users/7?tag=rust&limit=3").1, "7:rust:3");
+        assert_eq!(get("/users/7/posts/first").1, "7/first");
+        assert_eq!(get("/users/7/posts/a%20b%2B1").1, "7/a b+1");
+        assert_eq!(get("/users/seven?tag=rust").0, 400);
+        assert_eq!(get("/users/7").0, 400);
+
+        let res = client
+            .post(format!("http://{}/users", addr))
+            .header("content-type", "application/json")
+            .body(r#"{"name":"ann","age":30}"#)
+            .send()
+            .unwrap();
+        assert_eq!(res.status(), 201);
+        assert_eq!(res.headers()["content-type"], "application/json");
+        assert_eq!(res.text().unwrap(), r#"{"name":"new-ann","age":30}"#);
+
+        let post_json = |content_type: &str, body: &'static str| {
+            client
+                .post(format!("http://{}/users", addr))
+                .header("content-type", content_type)
+                .body(body)
+                .send()
+                .unwrap()
+                .status()
+                .as_u16()
+        };
+
+        assert_eq!(post_json("text/plain", r#"{"name":"ann","age":30}"#), 415);
+        assert_eq!(post_json("application/json", r#"{"name":"ann""#), 400);
+        assert_eq!(
+            post_json("application/json", r#"{"name":"ann","age":-1}"#),
+            422
+        );
+
+        app.shutdown();
+        handle.join().unwrap();
+    }
+}
@@ -28,7 +28,7 @@ mod tests {
 
     fn setup() -> Arc<App> {
         let router = Router::new("files").get("", files).post("", files_body);
-        App::new(BASE_URL).with_router(router).build()
+        App::new(BASE_URL).with_router(router).build().unwrap()
     }
 
     #[test]
@@ -108,7 +108,9 @@ mod tests {
     fn files(req: &Request, res: Response) -> ServerResponse {
         let file_path = TEST_FILE_PATH;
 
-        let Ok(f) = fs::File::open(format!("{}/{}", file_path, req.query)) else {
+        let file_name = req.query("q").unwrap_or_default();
+
+        let Ok(f) = fs::File::open(format!("{}/{}", file_path, file_name)) else {
             return res.status(Status::NotFound).into_response();
         };
 
@@ -132,15 +134,15 @@ mod tests {
         let file_path_name = format!("{}/{}", dir, TEST_FILE_NAME);
         let mut file = fs::File::create_new(&file_path_name)?;
 
-        file.write_all(request.body.as_bytes())?;
+        file.write_all(&request.body)?;
         file.seek(std::io::SeekFrom::Start(0))?;
 
-        let mut body = String::new();
-        file.read_to_string(&mut body)?;
+        let mut body = Vec::new();
+        file.read_to_end(&mut body)?;
 
         res.status(Status::Created)
             .content_type(ContentType::OctetStream)
-            .body(body.into_bytes())
+            .body(body)
             .into()
     }
 }
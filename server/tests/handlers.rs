@@ -0,0 +1,63 @@
+mod test_utils;
+
+#[cfg(test)]
+mod tests {
+
+    use std::{
+        sync::{
+            Arc,
+            atomic::{AtomicUsize, Ordering},
+        },
+        thread,
+    };
+
+    use server::{
+        app::App,
+        models::{request::Request, response::Response},
+        router::Router,
+    };
+
+    use crate::test_utils::{local_listener, wait_until_server_ready};
+
+    #[test]
+    fn closures_capture_state() {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let greeting = String::from("hello");
+
+        let counter = Arc::clone(&hits);
+        let router = Router::new("api").get("greet/:name", move |req: &Request, res: Response| {
+            let body = format!("{} {}", greeting, req.param("name").unwrap_or_default());
+            res.body(body.into_bytes())
+        });
+
+        let app = App::from_listener(local_listener())
+            .unwrap()
+            .get("count", move || {
+                let count = counter.fetch_add(1, Ordering::SeqCst) + 1;
+                count.to_string()
+            })
+            .with_router(router)
+            .build()
+            .unwrap();
+        let addr = app.local_addr().unwrap().to_string();
+
+        let server = Arc::clone(&app);
+        let handle = thread::spawn(move || server.run());
+
+        wait_until_server_ready(&addr);
+
+        for expected in ["1", "2"] {
+            let res = reqwest::blocking::get(format!("http://{}/count", addr))
+                .expect("Couldn't send request to the server");
+            assert_eq!(res.text().unwrap(), expected);
+        }
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+
+        let res = reqwest::blocking::get(format!("http://{}/api/greet/bob", addr))
+            .expect("Couldn't send request to the server");
+        assert_eq!(res.text().unwrap(), "hello bob");
+
+        app.shutdown();
+        handle.join().unwrap();
+    }
+}
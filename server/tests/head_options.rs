@@ -0,0 +1,123 @@
+mod test_utils;
+
+#[cfg(test)]
+mod tests {
+
+    use std::{sync::Arc, thread};
+
+    use reqwest::{Method, blocking::Client};
+    use server::{
+        app::{App, ServerResponse},
+        models::{headers::Header, request::Request, response::Response, status::Status},
+    };
+
+    use crate::test_utils::{local_listener, send, wait_until_server_ready};
+
+    #[test]
+    fn head_runs_get_handler_without_body() {
+        let app = App::from_listener(local_listener())
+            .unwrap()
+            .get("health", health)
+            .build()
+            .unwrap();
+        let addr = app.local_addr().unwrap().to_string();
+
+        let server = Arc::clone(&app);
+        let handle = thread::spawn(move || server.run());
+
+        wait_until_server_ready(&addr);
+
+        let res = Client::new()
+            .head(format!("http://{}/health", addr))
+            .send()
+            .expect("Couldn't send request to the server");
+
+        assert_eq!(res.status(), 200);
+        assert_eq!(res.headers()["content-length"], "2");
+        assert_eq!(res.text().unwrap(), "");
+
+        app.shutdown();
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn options_lists_allowed_methods() {
+        let app = App::from_listener(local_listener())
+            .unwrap()
+            .get("health", health)
+            .post("health", health)
+            .options("custom", custom_options)
+            .build()
+            .unwrap();
+        let addr = app.local_addr().unwrap().to_string();
+
+        let server = Arc::clone(&app);
+        let handle = thread::spawn(move || server.run());
+
+        wait_until_server_ready(&addr);
+
+        let client = Client::new();
+
+        let res = client
+            .request(Method::OPTIONS, format!("http://{}/health", addr))
+            .send()
+            .expect("Couldn't send request to the server");
+
+        assert_eq!(res.status(), 204);
+        assert_eq!(res.headers()["allow"], "GET, POST, HEAD, OPTIONS");
+        assert!(res.headers().get("content-length").is_none());
+        assert!(res.headers().get("content-type").is_none());
+
+        let res = client
+            .request(Method::OPTIONS, format!("http://{}/custom", addr))
+            .send()
+            .expect("Couldn't send request to the server");
+
+        assert_eq!(res.status(), 200);
+        assert_eq!(res.headers()["allow"], "OPTIONS, TRACE");
+
+        app.shutdown();
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn not_modified_has_no_body() {
+        let app = App::from_listener(local_listener())
+            .unwrap()
+            .get("cached", cached)
+            .build()
+            .unwrap();
+        let addr = app.local_addr().unwrap().to_string();
+
+        let server = Arc::clone(&app);
+        let handle = thread::spawn(move || server.run());
+
+        wait_until_server_ready(&addr);
+
+        let response = send(&addr, b"GET /cached HTTP/1.1\r\nConnection: close\r\n\r\n");
+
+        assert!(response.starts_with("HTTP/1.1 304 Not Modified\r\n"));
+        assert!(response.contains("Etag: \"v1\""));
+        assert!(!response.to_lowercase().contains("content-length"));
+        assert!(!response.to_lowercase().contains("transfer-encoding"));
+        assert!(response.ends_with("\r\n\r\n"));
+
+        app.shutdown();
+        handle.join().unwrap();
+    }
+
+    fn health(_: &Request, res: Response) -> ServerResponse {
+        res.body(b"ok".to_vec()).status(Status::Ok).into()
+    }
+
+    fn cached(_: &Request, res: Response) -> ServerResponse {
+        res.status(Status::NotModified)
+            .header("ETag", "\"v1\"")
+            .body(b"stale".to_vec())
+            .into()
+    }
+
+    fn custom_options(_: &Request, res: Response) -> ServerResponse {
+        res.header(Header::Allow, "OPTIONS, TRACE").into()
+    }
+}
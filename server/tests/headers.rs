@@ -0,0 +1,72 @@
+mod test_utils;
+
+#[cfg(test)]
+mod tests {
+
+    use std::{
+        io::{Read, Write},
+        net::TcpStream,
+        sync::Arc,
+        thread,
+    };
+
+    use server::{
+        app::{App, ServerResponse},
+        models::{request::Request, response::Response},
+    };
+
+    use crate::test_utils::{local_listener, wait_until_server_ready};
+
+    #[test]
+    fn request_headers_are_parsed_by_name() {
+        let app = App::from_listener(local_listener())
+            .unwrap()
+            .get("headers", echo_headers)
+            .build()
+            .unwrap();
+        let addr = app.local_addr().unwrap().to_string();
+
+        let server = Arc::clone(&app);
+        let handle = thread::spawn(move || server.run());
+
+        wait_until_server_ready(&addr);
+
+        let mut stream = TcpStream::connect(&addr).unwrap();
+        stream
+            .write_all(
+                b"GET /headers HTTP/1.1\r\n\
+                  X-Not-Host: evil.example\r\n\
+                  HOST: real.example\r\n\
+                  User-Agent: Mozilla/5.0 (X11; Linux x86_64)\r\n\
+                  X-Tag: first\r\n\
+                  x-tag:second  \r\n\
+                  Connection: close\r\n\
+                  \r\n",
+            )
+            .unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        let body = response.split("\r\n\r\n").nth(1).unwrap();
+        assert_eq!(
+            body,
+            "real.example|Mozilla/5.0 (X11; Linux x86_64)|first,second|none"
+        );
+
+        app.shutdown();
+        handle.join().unwrap();
+    }
+
+    fn echo_headers(req: &Request, res: Response) -> ServerResponse {
+        let body = format!(
+            "{}|{}|{}|{}",
+            req.host(),
+            req.user_agent(),
+            req.headers.get_all("X-TAG").collect::<Vec<_>>().join(","),
+            req.header("x-missing").unwrap_or("none")
+        );
+
+        res.body(body.into_bytes()).into()
+    }
+}
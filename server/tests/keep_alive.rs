@@ -0,0 +1,180 @@
+mod test_utils;
+
+#[cfg(test)]
+mod tests {
+
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpStream,
+        sync::Arc,
+        thread,
+        time::Duration,
+    };
+
+    use server::{
+        app::{App, ServerResponse},
+        models::{request::Request, response::Response},
+    };
+
+    use crate::test_utils::{local_listener, wait_until_server_ready};
+
+    // Reads one response off the connection and returns its head, lowercased,
+    // and its body.
+    fn read_response(reader: &mut BufReader<TcpStream>) -> (String, String) {
+        let mut head = String::new();
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if line == "\r\n" || line.is_empty() {
+                break;
+            }
+            head.push_str(&line.to_ascii_lowercase());
+        }
+
+        let length = head
+            .lines()
+            .find_map(|l| l.strip_prefix("content-length: "))
+            .map(|l| l.trim().parse().unwrap())
+            .unwrap_or(0);
+
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).unwrap();
+
+        (head, String::from_utf8(body).unwrap())
+    }
+
+    fn is_closed(reader: &mut BufReader<TcpStream>) -> bool {
+        let mut buf = [0; 1];
+        matches!(reader.read(&mut buf), Ok(0) | Err(_))
+    }
+
+    fn connect(addr: &str) -> (TcpStream, BufReader<TcpStream>) {
+        let stream = TcpStream::connect(addr).unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        let reader = BufReader::new(stream.try_clone().unwrap());
+
+        (stream, reader)
+    }
+
+    #[test]
+    fn connections_are_reused() {
+        let app = App::builder()
+            .max_requests_per_connection(3)
+            .keep_alive(Duration::from_millis(200))
+            .listener(local_listener())
+            .unwrap()
+            .post("echo", echo)
+            .build()
+            .unwrap();
+        let addr = app.local_addr().unwrap().to_string();
+
+        let server = Arc::clone(&app);
+        let handle = thread::spawn(move || server.run());
+
+        wait_until_server_ready(&addr);
+
+        // HTTP/1.1 stays open until the client asks to close.
+        let (mut stream, mut reader) = connect(&addr);
+        for body in ["one", "two"] {
+            write!(
+                stream,
+                "POST /echo HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .unwrap();
+            let (head, echoed) = read_response(&mut reader);
+            assert!(head.contains("connection: keep-alive"));
+            assert_eq!(echoed, body);
+        }
+        write!(
+            stream,
+            "POST /echo HTTP/1.1\r\nConnection: close\r\nContent-Length: 0\r\n\r\n"
+        )
+        .unwrap();
+        let (head, _) = read_response(&mut reader);
+        assert!(head.contains("connection: close"));
+        assert!(is_closed(&mut reader));
+
+        // HTTP/1.0 closes unless the client asks for keep-alive.
+        let (mut stream, mut reader) = connect(&addr);
+        write!(stream, "POST /echo HTTP/1.0\r\n\r\n").unwrap();
+        let (head, _) = read_response(&mut reader);
+        assert!(head.contains("connection: close"));
+        assert!(is_closed(&mut reader));
+
+        let (mut stream, mut reader) = connect(&addr);
+        write!(
+            stream,
+            "POST /echo HTTP/1.0\r\nConnection: keep-alive\r\n\r\n"
+        )
+        .unwrap();
+        let (head, _) = read_response(&mut reader);
+        assert!(head.contains("connection: keep-alive"));
+
+        // Idle connections are dropped after the keep-alive timeout.
+        thread::sleep(Duration::from_millis(400));
+        assert!(is_closed(&mut reader));
+
+        // The last request allowed on a connection closes it.
+        let (mut stream, mut reader) = connect(&addr);
+        for i in 1..=3 {
+            write!(stream, "POST /echo HTTP/1.1\r\n\r\n").unwrap();
+            let (head, _) = read_response(&mut reader);
+            assert_eq!(head.contains("connection: close"), i == 3);
+        }
+        assert!(is_closed(&mut reader));
+
+        app.shutdown();
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn pipelined_requests_are_answered_in_order() {
+        let app = App::from_listener(local_listener())
+            .unwrap()
+            .post("echo", echo)
+            .build()
+            .unwrap();
+        let addr = app.local_addr().unwrap().to_string();
+
+        let server = Arc::clone(&app);
+        let handle = thread::spawn(move || server.run());
+
+        wait_until_server_ready(&addr);
+
+        let (mut stream, mut reader) = connect(&addr);
+        stream
+            .write_all(
+                b"POST /echo HTTP/1.1\r\nContent-Length: 5\r\n\r\nfirst\
+                  POST /missing HTTP/1.1\r\n\r\n\
+                  POST /echo HTTP/1.1\r\nContent-Length: 6\r\n\r\nsecond\
+                  POST /echo HTTP/1.1\r\nConnection: close\r\nContent-Length: 5\r\n\r\nthird",
+            )
+            .unwrap();
+
+        let (head, body) = read_response(&mut reader);
+        assert!(head.starts_with("http/1.1 200"));
+        assert_eq!(body, "first");
+
+        let (head, _) = read_response(&mut reader);
+        assert!(head.starts_with("http/1.1 404"));
+
+        let (_, body) = read_response(&mut reader);
+        assert_eq!(body, "second");
+
+        let (head, body) = read_response(&mut reader);
+        assert!(head.contains("connection: close"));
+        assert_eq!(body, "third");
+        assert!(is_closed(&mut reader));
+
+        app.shutdown();
+        handle.join().unwrap();
+    }
+
+    fn echo(req: &Request, res: Response) -> ServerResponse {
+        res.body(req.body.to_vec()).into()
+    }
+}
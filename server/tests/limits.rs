@@ -0,0 +1,165 @@
+mod test_utils;
+
+#[cfg(test)]
+mod tests {
+
+    use std::{sync::Arc, thread};
+
+    use server::{
+        app::{App, Route, ServerResponse, limits::Limits},
+        models::{request::Request, response::Response},
+    };
+
+    use crate::test_utils::{local_listener, send, wait_until_server_ready};
+
+    #[test]
+    fn oversized_requests_are_rejected() {
+        let app = App::builder()
+            .limits(Limits {
+                max_uri_length: 64,
+                max_header_bytes: 256,
+                max_headers: 4,
+                max_body_size: 16,
+            })
+            .listener(local_listener())
+            .unwrap()
+            .post("small", echo)
+            .post("large", Route::new(echo).max_body_size(1024))
+            .build()
+            .unwrap();
+        let addr = app.local_addr().unwrap().to_string();
+
+        let server = Arc::clone(&app);
+        let handle = thread::spawn(move || server.run());
+
+        wait_until_server_ready(&addr);
+
+        let body = "a".repeat(100);
+        let cases = [
+            (
+                format!("POST /small?q={} HTTP/1.1\r\n\r\n", "a".repeat(64)),
+                "HTTP/1.1 414 URI Too Long",
+            ),
+            (
+                format!(
+                    "POST /small HTTP/1.1\r\nX-Big: {}\r\n\r\n",
+                    "a".repeat(300)
+                ),
+                "HTTP/1.1 431 Request Header Fields Too Large",
+            ),
+            // Headers filling exactly 256 bytes fit; the blank line after
+            // them isn't counted. The same goes for trailers.
+            (
+                format!(
+                    "POST /small HTTP/1.1\r\nConnection: close\r\nX-Pad: {}\r\n\r\n",
+                    "a".repeat(228)
+                ),
+                "HTTP/1.1 200 OK",
+            ),
+            (
+                format!(
+                    "POST /small HTTP/1.1\r\nConnection: close\r\nX-Pad: {}\r\n\r\n",
+                    "a".repeat(229)
+                ),
+                "HTTP/1.1 431 Request Header Fields Too Large",
+            ),
+            (
+                format!(
+                    "POST /small HTTP/1.1\r\nConnection: close\r\nTransfer-Encoding: chunked\r\n\r\n\
+                     0\r\nX-Pad: {}\r\n\r\n",
+                    "a".repeat(247)
+                ),
+                "HTTP/1.1 200 OK",
+            ),
+            (
+                format!(
+                    "POST /small HTTP/1.1\r\nConnection: close\r\nTransfer-Encoding: chunked\r\n\r\n\
+                     0\r\nX-Pad: {}\r\n\r\n",
+                    "a".repeat(248)
+                ),
+                "HTTP/1.1 431 Request Header Fields Too Large",
+            ),
+            (
+                "POST /small HTTP/1.1\r\nA: 1\r\nB: 2\r\nC: 3\r\nD: 4\r\nE: 5\r\n\r\n".to_string(),
+                "HTTP/1.1 431 Request Header Fields Too Large",
+            ),
+            // Refused from the header alone, without allocating for it.
+            (
+                "POST /small HTTP/1.1\r\nContent-Length: 100000000000\r\n\r\n".to_string(),
+                "HTTP/1.1 413 Content Too Large",
+            ),
+            (
+                format!(
+                    "POST /small HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n64\r\n{}\r\n0\r\n\r\n",
+                    body
+                ),
+                "HTTP/1.1 413 Content Too Large",
+            ),
+            (
+                format!(
+                    "POST /large HTTP/1.1\r\nConnection: close\r\nContent-Length: 100\r\n\r\n{}",
+                    body
+                ),
+                "HTTP/1.1 200 OK",
+            ),
+            (
+                "POST /small HTTP/1.1\r\nConnection: close\r\nContent-Length: 16\r\n\r\nsixteen-bytes-ok"
+                    .to_string(),
+                "HTTP/1.1 200 OK",
+            ),
+        ];
+
+        for (raw, expected) in cases {
+            let response = send(&addr, raw.as_bytes());
+            assert!(
+                response.starts_with(expected),
+                "expected `{}`, got `{}`",
+                expected,
+                response
+            );
+        }
+
+        app.shutdown();
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn maximum_limits_mean_unlimited() {
+        let app = App::builder()
+            .limits(Limits {
+                max_uri_length: usize::MAX,
+                max_header_bytes: usize::MAX,
+                max_headers: usize::MAX,
+                max_body_size: u64::MAX,
+            })
+            .listener(local_listener())
+            .unwrap()
+            .post("echo", echo)
+            .build()
+            .unwrap();
+        let addr = app.local_addr().unwrap().to_string();
+
+        let server = Arc::clone(&app);
+        let handle = thread::spawn(move || server.run());
+
+        wait_until_server_ready(&addr);
+
+        let response = send(
+            &addr,
+            format!(
+                "POST /echo?q={} HTTP/1.1\r\nConnection: close\r\nContent-Length: 2\r\n\r\nhi",
+                "a".repeat(10_000)
+            )
+            .as_bytes(),
+        );
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+        assert!(response.ends_with("hi"));
+
+        app.shutdown();
+        handle.join().unwrap();
+    }
+
+    fn echo(req: &Request, res: Response) -> ServerResponse {
+        res.body(req.body.to_vec()).into()
+    }
+}
@@ -0,0 +1,112 @@
+mod test_utils;
+
+#[cfg(test)]
+mod tests {
+
+    use std::{
+        sync::{Arc, Mutex},
+        thread,
+    };
+
+    use server::{
+        app::{App, ServerResponse},
+        middleware::{Middleware, Next},
+        models::{request::Request, response::Response, status::Status},
+        router::Router,
+    };
+
+    use crate::test_utils::{local_listener, wait_until_server_ready};
+
+    struct Log(Arc<Mutex<Vec<String>>>);
+
+    impl Middleware for Log {
+        fn handle(&self, req: &mut Request, next: Next<'_>) -> ServerResponse {
+            self.0.lock().unwrap().push(format!("before {}", req.path));
+            let res = next.run(req)?;
+            self.0.lock().unwrap().push(format!("after {}", req.path));
+
+            Ok(res)
+        }
+    }
+
+    fn strip_version(req: &mut Request, next: Next<'_>) -> ServerResponse {
+        if let Some(path) = req.path.strip_prefix("v1/") {
+            req.path = path.to_string();
+        }
+
+        next.run(req)
+    }
+
+    fn require_token(req: &mut Request, next: Next<'_>) -> ServerResponse {
+        if req.query_string != "token=secret" {
+            return Response::default().status(Status::Unauthorized).into();
+        }
+
+        next.run(req)
+    }
+
+    #[test]
+    fn global_and_scoped_middleware() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+
+        let admin = Router::new("admin")
+            .get("stats", stats)
+            .layer(require_token)
+            .layer(|req: &mut Request, next: Next<'_>| -> ServerResponse {
+                let res = next.run(req)?;
+                let body = format!("[{}]", req.param("section").unwrap_or("all"));
+
+                Ok(res.body(body.into_bytes()))
+            });
+
+        let app = App::from_listener(local_listener())
+            .unwrap()
+            .layer(Log(Arc::clone(&log)))
+            .layer(strip_version)
+            .get("public", stats)
+            .with_router(admin)
+            .build()
+            .unwrap();
+        let addr = app.local_addr().unwrap().to_string();
+
+        let server = Arc::clone(&app);
+        let handle = thread::spawn(move || server.run());
+
+        wait_until_server_ready(&addr);
+
+        let get = |path: &str| {
+            let res = reqwest::blocking::get(format!("http://{}{}", addr, path))
+                .expect("Couldn't send request to the server");
+            (res.status().as_u16(), res.text().unwrap())
+        };
+
+        assert_eq!(get("/v1/public"), (200, String::from("stats")));
+        assert_eq!(get("/admin/stats").0, 401);
+        assert_eq!(
+            get("/v1/admin/stats?token=secret"),
+            (200, String::from("[all]"))
+        );
+        assert_eq!(get("/missing").0, 404);
+
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![
+                "before v1/public",
+                "after public",
+                "before admin/stats",
+                "after admin/stats",
+                "before v1/admin/stats",
+                "after admin/stats",
+                "before missing",
+                "after missing",
+            ]
+        );
+
+        app.shutdown();
+        handle.join().unwrap();
+    }
+
+    fn stats(_: &Request, res: Response) -> ServerResponse {
+        res.body(b"stats".to_vec()).into()
+    }
+}
@@ -0,0 +1,94 @@
+mod test_utils;
+
+#[cfg(test)]
+mod tests {
+
+    use std::{sync::Arc, thread};
+
+    use reqwest::blocking::Client;
+    use server::{
+        app::{App, ServerResponse},
+        models::{request::Request, response::Response},
+    };
+
+    use crate::test_utils::{local_listener, send, wait_until_server_ready};
+
+    #[test]
+    fn handler_panics_become_500s() {
+        let app = App::builder()
+            .workers(1)
+            .listener(local_listener())
+            .unwrap()
+            .get("panic", panics)
+            .get("ok", ok)
+            .build()
+            .unwrap();
+        let addr = app.local_addr().unwrap().to_string();
+
+        let server = Arc::clone(&app);
+        let handle = thread::spawn(move || server.run());
+
+        wait_until_server_ready(&addr);
+
+        let client = Client::new();
+        let get = |path: &str| {
+            client
+                .get(format!("http://{}/{}", addr, path))
+                .send()
+                .expect("Couldn't send request to the server")
+        };
+
+        // More panics than workers: the single worker has to survive each.
+        for _ in 0..3 {
+            let res = get("panic");
+            assert_eq!(res.status(), 500);
+            assert_eq!(res.headers()["connection"], "close");
+        }
+
+        let res = get("ok");
+        assert_eq!(res.status(), 200);
+        assert_eq!(res.text().unwrap(), "still here");
+
+        app.shutdown();
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn handler_errors_become_500s() {
+        let app = App::from_listener(local_listener())
+            .unwrap()
+            .get("fail", fails)
+            .get("ok", ok)
+            .build()
+            .unwrap();
+        let addr = app.local_addr().unwrap().to_string();
+
+        let server = Arc::clone(&app);
+        let handle = thread::spawn(move || server.run());
+
+        wait_until_server_ready(&addr);
+
+        let response = send(&addr, b"GET /fail HTTP/1.1\r\n\r\nGET /ok HTTP/1.1\r\n\r\n");
+        assert!(response.starts_with("HTTP/1.1 500 Internal Server Error"));
+        assert!(response.to_lowercase().contains("connection: close"));
+        assert!(!response.contains("still here"));
+
+        let response = send(&addr, b"GET /ok HTTP/1.1\r\nConnection: close\r\n\r\n");
+        assert!(response.ends_with("still here"));
+
+        app.shutdown();
+        handle.join().unwrap();
+    }
+
+    fn fails(_req: &Request, _res: Response) -> ServerResponse {
+        Err("boom".into())
+    }
+
+    fn panics(_req: &Request, _res: Response) -> ServerResponse {
+        panic!("handler blew up");
+    }
+
+    fn ok(_req: &Request, res: Response) -> ServerResponse {
+        res.body(b"still here".to_vec()).into()
+    }
+}
@@ -0,0 +1,164 @@
+mod test_utils;
+
+#[cfg(test)]
+mod tests {
+
+    use std::{sync::Arc, thread};
+
+    use server::{
+        app::{App, ServerResponse},
+        models::{request::Request, response::Response},
+    };
+
+    use crate::test_utils::{local_listener, send, wait_until_server_ready};
+
+    #[test]
+    fn malformed_requests_get_error_responses() {
+        let app = App::from_listener(local_listener())
+            .unwrap()
+            .get("", root)
+            .build()
+            .unwrap();
+        let addr = app.local_addr().unwrap().to_string();
+
+        let server = Arc::clone(&app);
+        let handle = thread::spawn(move || server.run());
+
+        wait_until_server_ready(&addr);
+
+        let cases: Vec<(Vec<u8>, &str)> = vec![
+            (b"GARBAGE\r\n\r\n".to_vec(), "HTTP/1.1 400 Bad Request"),
+            (b"GET /\r\n\r\n".to_vec(), "HTTP/1.1 400 Bad Request"),
+            (
+                b"GET / HTTP/1.1 extra\r\n\r\n".to_vec(),
+                "HTTP/1.1 400 Bad Request",
+            ),
+            (
+                b"BREW / HTTP/1.1\r\n\r\n".to_vec(),
+                "HTTP/1.1 501 Not Implemented",
+            ),
+            (
+                b"GET / HTTP/2.0\r\n\r\n".to_vec(),
+                "HTTP/1.1 505 HTTP Version Not Supported",
+            ),
+            (
+                b"GET / HTTP/1.1\r\nbad header\r\n\r\n".to_vec(),
+                "HTTP/1.1 400 Bad Request",
+            ),
+            (
+                b"GET / HTTP/1.1\r\nContent-Length: abc\r\n\r\n".to_vec(),
+                "HTTP/1.1 400 Bad Request",
+            ),
+            (
+                b"POST / HTTP/1.1\r\nContent-Length: +3\r\n\r\nabc".to_vec(),
+                "HTTP/1.1 400 Bad Request",
+            ),
+            (
+                b"POST / HTTP/1.1\r\nContent-Length: 3\r\nContent-Length: 4\r\n\r\nabcd".to_vec(),
+                "HTTP/1.1 400 Bad Request",
+            ),
+            (
+                format!("GET /{} HTTP/1.1\r\n\r\n", "a".repeat(10_000)).into_bytes(),
+                "HTTP/1.1 414 URI Too Long",
+            ),
+            (
+                format!("GET / HTTP/1.1\r\nX-Big: {}\r\n\r\n", "a".repeat(20_000)).into_bytes(),
+                "HTTP/1.1 431 Request Header Fields Too Large",
+            ),
+            (
+                (0..200)
+                    .map(|i| format!("X-{}: v\r\n", i))
+                    .fold("GET / HTTP/1.1\r\n".to_string(), |acc, h| acc + &h)
+                    .into_bytes(),
+                "HTTP/1.1 431 Request Header Fields Too Large",
+            ),
+        ];
+
+        for (raw, expected) in cases {
+            let response = send(&addr, &raw);
+            assert!(
+                response.starts_with(expected),
+                "expected `{}`, got `{}`",
+                expected,
+                response
+            );
+        }
+
+        // The workers survive all of the above.
+        let response = send(&addr, b"GET / HTTP/1.1\r\nConnection: close\r\n\r\n");
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+
+        app.shutdown();
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn absolute_and_asterisk_targets_are_accepted() {
+        let app = App::from_listener(local_listener())
+            .unwrap()
+            .get("", root)
+            .get("host", host)
+            .build()
+            .unwrap();
+        let addr = app.local_addr().unwrap().to_string();
+
+        let server = Arc::clone(&app);
+        let handle = thread::spawn(move || server.run());
+
+        wait_until_server_ready(&addr);
+
+        let cases: [(&[u8], &str); 6] = [
+            (
+                b"GET http://example.com HTTP/1.1\r\nConnection: close\r\n\r\n",
+                "HTTP/1.1 200 OK",
+            ),
+            (
+                b"OPTIONS * HTTP/1.1\r\nConnection: close\r\n\r\n",
+                "HTTP/1.1 204 No Content",
+            ),
+            (b"GET * HTTP/1.1\r\n\r\n", "HTTP/1.1 400 Bad Request"),
+            (
+                b"GET ftp://example.com/ HTTP/1.1\r\n\r\n",
+                "HTTP/1.1 400 Bad Request",
+            ),
+            (
+                b"GET http:///host HTTP/1.1\r\n\r\n",
+                "HTTP/1.1 400 Bad Request",
+            ),
+            (
+                b"GET example.com HTTP/1.1\r\n\r\n",
+                "HTTP/1.1 400 Bad Request",
+            ),
+        ];
+
+        for (raw, expected) in cases {
+            let response = send(&addr, raw);
+            assert!(
+                response.starts_with(expected),
+                "expected `{}`, got `{}`",
+                expected,
+                response
+            );
+        }
+
+        // The target's authority takes the place of the Host header.
+        let response = send(
+            &addr,
+            b"GET http://example.com:8080/host HTTP/1.1\r\nHost: other\r\nConnection: close\r\n\r\n",
+        );
+        assert!(response.ends_with("example.com:8080"), "{}", response);
+
+        app.shutdown();
+        handle.join().unwrap();
+    }
+
+    fn host(req: &Request, res: Response) -> ServerResponse {
+        let host = req.headers.get("host").unwrap_or_default().to_string();
+
+        res.body(host.into_bytes()).into()
+    }
+
+    fn root(_req: &Request, res: Response) -> ServerResponse {
+        res.into()
+    }
+}
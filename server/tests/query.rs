@@ -0,0 +1,66 @@
+mod test_utils;
+
+#[cfg(test)]
+mod tests {
+
+    use std::{sync::Arc, thread};
+
+    use server::{
+        app::{App, ServerResponse},
+        models::{request::Request, response::Response},
+    };
+
+    use crate::test_utils::{local_listener, wait_until_server_ready};
+
+    #[test]
+    fn query_parameters_are_decoded() {
+        let app = App::from_listener(local_listener())
+            .unwrap()
+            .get("search", search)
+            .build()
+            .unwrap();
+        let addr = app.local_addr().unwrap().to_string();
+
+        let server = Arc::clone(&app);
+        let handle = thread::spawn(move || server.run());
+
+        wait_until_server_ready(&addr);
+
+        let get = |query: &str| {
+            reqwest::blocking::get(format!("http://{}/search{}", addr, query))
+                .expect("Couldn't send request to the server")
+                .text()
+                .unwrap()
+        };
+
+        assert_eq!(
+            get("?a=1&b=2&tag=x&tag=y%20z&q=caf%C3%A9+au+lait"),
+            "a=1|b=2|tags=x,y z|q=café au lait|name=missing|raw=a=1&b=2&tag=x&tag=y%20z&q=caf%C3%A9+au+lait"
+        );
+        assert_eq!(
+            get("?name="),
+            "a=missing|b=missing|tags=|q=missing|name=|raw=name="
+        );
+        assert_eq!(
+            get(""),
+            "a=missing|b=missing|tags=|q=missing|name=missing|raw="
+        );
+
+        app.shutdown();
+        handle.join().unwrap();
+    }
+
+    fn search(req: &Request, res: Response) -> ServerResponse {
+        let body = format!(
+            "a={}|b={}|tags={}|q={}|name={}|raw={}",
+            req.query("a").unwrap_or("missing"),
+            req.query("b").unwrap_or("missing"),
+            req.query_all("tag").collect::<Vec<_>>().join(","),
+            req.query("q").unwrap_or("missing"),
+            req.query("name").unwrap_or("missing"),
+            req.query_string
+        );
+
+        res.body(body.into_bytes()).into()
+    }
+}
@@ -0,0 +1,154 @@
+mod test_utils;
+
+#[cfg(test)]
+mod tests {
+
+    use std::{
+        io::{Read, Write},
+        net::TcpStream,
+        sync::Arc,
+        thread,
+    };
+
+    use server::{
+        app::{App, ServerResponse},
+        models::{headers::Header, request::Request, response::Response, status::Status},
+    };
+
+    use crate::test_utils::{local_listener, wait_until_server_ready};
+
+    #[test]
+    fn custom_headers_are_sent() {
+        let app = App::from_listener(local_listener())
+            .unwrap()
+            .get("login", login)
+            .get("redirect", redirect)
+            .get("checked", checked)
+            .build()
+            .unwrap();
+        let addr = app.local_addr().unwrap().to_string();
+
+        let server = Arc::clone(&app);
+        let handle = thread::spawn(move || server.run());
+
+        wait_until_server_ready(&addr);
+
+        let mut stream = TcpStream::connect(&addr).unwrap();
+        stream
+            .write_all(b"GET /login HTTP/1.1\r\nConnection: close\r\n\r\n")
+            .unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let lines = head.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines[0], "HTTP/1.1 201 Created");
+        assert!(lines.contains(&"Location: /home"));
+        assert!(lines.contains(&"Cache-Control: no-store"));
+        assert!(lines.contains(&"Set-Cookie: session=abc"));
+        assert!(lines.contains(&"Set-Cookie: theme=dark"));
+        assert!(lines.contains(&"X-Request-Id: 42"));
+        assert!(lines.contains(&"Content-Length: 2"));
+        assert!(lines.contains(&"Content-Type: text/plain"));
+        assert!(!lines.iter().any(|l| l.starts_with("Content-Encoding")));
+        assert!(!lines.iter().any(|l| l.starts_with("X-Empty")));
+        assert_eq!(body, "ok");
+
+        // A CR/LF smuggled in through a header value can't start a header
+        // of its own, and neither can a name that isn't a token.
+        let mut stream = TcpStream::connect(&addr).unwrap();
+        stream
+            .write_all(
+                b"GET /redirect?to=%2Fx%0D%0ASet-Cookie%3A%20pwn%3D1 HTTP/1.1\r\n\
+                  Connection: close\r\n\r\n",
+            )
+            .unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        let (head, _) = response.split_once("\r\n\r\n").unwrap();
+        let lines = head.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines[0], "HTTP/1.1 302 Found");
+        assert!(lines.contains(&"X-Safe: yes"));
+        assert!(!lines.iter().any(|l| l.to_lowercase().contains("pwn")));
+        assert!(!lines.iter().any(|l| l.starts_with("Location")));
+        assert!(!lines.iter().any(|l| l.contains("X-Bad")));
+
+        // `try_header` lets a handler find out instead.
+        let mut stream = TcpStream::connect(&addr).unwrap();
+        stream
+            .write_all(b"GET /checked?to=%2Fx%0D%0Ay HTTP/1.1\r\nConnection: close\r\n\r\n")
+            .unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        assert!(head.starts_with("HTTP/1.1 400 Bad Request"));
+        assert!(!head.to_lowercase().contains("location"));
+        assert_eq!(body, "invalid value for header `location`");
+
+        app.shutdown();
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn buffered_responses_serialize() {
+        let bytes = Response::default()
+            .status(Status::Created)
+            .header(Header::Location, "/home")
+            .body(b"ok".to_vec())
+            .to_bytes();
+        let response = String::from_utf8(bytes).unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 201 Created\r\nContent-Length: 2\r\n"));
+        assert!(response.contains("Location: /home\r\n"));
+        assert!(response.ends_with("\r\n\r\nok"));
+
+        let bytes = Response::default()
+            .status(Status::NoContent)
+            .body(b"dropped".to_vec())
+            .to_bytes();
+        assert!(String::from_utf8(bytes).unwrap().ends_with("\r\n\r\n"));
+    }
+
+    fn login(_: &Request, res: Response) -> ServerResponse {
+        let mut res = res
+            .status(Status::Created)
+            .header(Header::Location, "/home")
+            .header(Header::SetCookie, "session=abc")
+            .header(Header::SetCookie, "theme=dark")
+            .header("X-Empty", "")
+            .body(b"ok".to_vec());
+
+        res.headers_mut().insert(Header::CacheControl, "no-store");
+        res.headers_mut().insert("X-Request-Id", "42");
+
+        res.into()
+    }
+
+    fn checked(req: &Request, res: Response) -> ServerResponse {
+        match res
+            .status(Status::Found)
+            .try_header(Header::Location, req.query("to").unwrap_or("/"))
+        {
+            Ok(res) => res.into(),
+            Err(e) => Response::default()
+                .status(Status::BadRequest)
+                .body(e.to_string().into_bytes())
+                .into(),
+        }
+    }
+
+    fn redirect(req: &Request, res: Response) -> ServerResponse {
+        res.status(Status::Found)
+            .header(Header::Location, req.query("to").unwrap_or("/"))
+            .header("X-Bad: 1\r\nX-Bad", "1")
+            .header("X-Safe", "yes")
+            .into()
+    }
+}
@@ -15,7 +15,7 @@ mod tests {
     #[test]
     fn root() {
         // Build the server
-        let app = App::new(BASE_URL).get("/", root_handler).build();
+        let app = App::new(BASE_URL).get("/", root_handler).build().unwrap();
 
         // Clone for the server thread
         let server = Arc::clone(&app);
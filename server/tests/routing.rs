@@ -0,0 +1,152 @@
+mod test_utils;
+
+#[cfg(test)]
+mod tests {
+
+    use std::{sync::Arc, thread};
+
+    use server::{
+        app::{App, ServerResponse},
+        models::{request::Request, response::Response},
+        router::{Router, tree::RouteError},
+    };
+
+    use crate::test_utils::{get, local_listener, wait_until_server_ready};
+
+    #[test]
+    fn nested_router_routes_are_reachable() {
+        let users = Router::new("users")
+            .get("", list_handler)
+            .get(":id/posts/:post_id", post_handler);
+        let app = App::from_listener(local_listener())
+            .unwrap()
+            .with_router(Router::new("api").route(users))
+            .build()
+            .unwrap();
+        let addr = app.local_addr().unwrap().to_string();
+
+        let server = Arc::clone(&app);
+        let handle = thread::spawn(move || server.run());
+
+        wait_until_server_ready(&addr);
+
+        assert_eq!(get(&addr, "/api/users"), (200, String::from("list")));
+        assert_eq!(
+            get(&addr, "/api/users/7/posts/42"),
+            (200, String::from("7:42"))
+        );
+        assert_eq!(get(&addr, "/api/users/7/posts").0, 404);
+        assert_eq!(get(&addr, "/api").0, 404);
+
+        app.shutdown();
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn whole_path_must_match() {
+        let app = App::from_listener(local_listener())
+            .unwrap()
+            .get("users/me", list_handler)
+            .get("users/:id", id_handler)
+            .get("café", list_handler)
+            .build()
+            .unwrap();
+        let addr = app.local_addr().unwrap().to_string();
+
+        let server = Arc::clone(&app);
+        let handle = thread::spawn(move || server.run());
+
+        wait_until_server_ready(&addr);
+
+        assert_eq!(get(&addr, "/users/me"), (200, String::from("list")));
+        assert_eq!(get(&addr, "/users/3"), (200, String::from("3")));
+        assert_eq!(get(&addr, "/users/3/extra").0, 404);
+
+        // Captures are percent-decoded like query parameters, but `+` is
+        // only a space in a query string.
+        assert_eq!(get(&addr, "/users/a%20b"), (200, String::from("a b")));
+        assert_eq!(get(&addr, "/users/a+b"), (200, String::from("a+b")));
+        assert_eq!(get(&addr, "/users/a%2Fb"), (200, String::from("a/b")));
+        assert_eq!(get(&addr, "/users/%C3%A9"), (200, String::from("é")));
+        assert_eq!(get(&addr, "/users/%zz").0, 400);
+        assert_eq!(get(&addr, "/users/%FF").0, 400);
+
+        // Static segments are compared decoded too, which is how a client
+        // sends anything outside ASCII.
+        assert_eq!(get(&addr, "/caf%C3%A9"), (200, String::from("list")));
+        assert_eq!(get(&addr, "/users/m%65"), (200, String::from("list")));
+
+        app.shutdown();
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn unregistered_method_is_not_allowed() {
+        let app = App::from_listener(local_listener())
+            .unwrap()
+            .post("users/:id", id_handler)
+            .get("users/:id", id_handler)
+            .build()
+            .unwrap();
+        let addr = app.local_addr().unwrap().to_string();
+
+        let server = Arc::clone(&app);
+        let handle = thread::spawn(move || server.run());
+
+        wait_until_server_ready(&addr);
+
+        let res = reqwest::blocking::Client::new()
+            .delete(format!("http://{}/users/3", addr))
+            .send()
+            .expect("Couldn't send request to the server");
+
+        assert_eq!(res.status(), 405);
+        assert_eq!(res.headers()["allow"], "GET, POST, HEAD, OPTIONS");
+        assert_eq!(get(&addr, "/posts/3").0, 404);
+
+        app.shutdown();
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn conflicting_routes_are_rejected() {
+        let duplicate = App::from_listener(local_listener())
+            .unwrap()
+            .get("users/:id", id_handler)
+            .with_router(Router::new("users").get(":id", list_handler))
+            .build();
+        assert!(matches!(duplicate, Err(RouteError::Duplicate { .. })));
+
+        let ambiguous = App::from_listener(local_listener())
+            .unwrap()
+            .get("users/:id", id_handler)
+            .get("users/:user_id/posts", list_handler)
+            .build();
+        assert!(matches!(ambiguous, Err(RouteError::Conflict { .. })));
+
+        let misplaced = App::from_listener(local_listener())
+            .unwrap()
+            .get("static/*path/raw", list_handler)
+            .build();
+        assert!(matches!(misplaced, Err(RouteError::Invalid { .. })));
+    }
+
+    fn list_handler(_: &Request, res: Response) -> ServerResponse {
+        res.body(b"list".to_vec()).into()
+    }
+
+    fn id_handler(req: &Request, res: Response) -> ServerResponse {
+        res.body(req.param("id").unwrap_or_default().as_bytes().to_vec())
+            .into()
+    }
+
+    fn post_handler(req: &Request, res: Response) -> ServerResponse {
+        let body = format!(
+            "{}:{}",
+            req.param("id").unwrap_or_default(),
+            req.param("post_id").unwrap_or_default()
+        );
+
+        res.body(body.into_bytes()).into()
+    }
+}
@@ -0,0 +1,84 @@
+mod test_utils;
+
+#[cfg(test)]
+mod tests {
+
+    use std::{
+        sync::{
+            Arc,
+            atomic::{AtomicUsize, Ordering},
+        },
+        thread,
+    };
+
+    use server::{
+        app::{App, ServerResponse},
+        models::{request::Request, response::Response, status::Status},
+    };
+
+    use crate::test_utils::{local_listener, wait_until_server_ready};
+
+    struct Config {
+        greeting: String,
+    }
+
+    #[derive(Default)]
+    struct Visits(AtomicUsize);
+
+    struct Cache;
+
+    #[test]
+    fn handlers_reach_registered_state() {
+        let app = App::from_listener(local_listener())
+            .unwrap()
+            .with_state(Config {
+                greeting: String::from("hello"),
+            })
+            .with_state(Visits::default())
+            .get("greet/:name", greet)
+            .get("missing", missing)
+            .build()
+            .unwrap();
+        let addr = app.local_addr().unwrap().to_string();
+
+        let server = Arc::clone(&app);
+        let handle = thread::spawn(move || server.run());
+
+        wait_until_server_ready(&addr);
+
+        for (name, expected) in [("ann", "hello ann #1"), ("bob", "hello bob #2")] {
+            let res = reqwest::blocking::get(format!("http://{}/greet/{}", addr, name))
+                .expect("Couldn't send request to the server");
+            assert_eq!(res.text().unwrap(), expected);
+        }
+
+        let res = reqwest::blocking::get(format!("http://{}/missing", addr))
+            .expect("Couldn't send request to the server");
+        assert_eq!(res.status(), 404);
+
+        app.shutdown();
+        handle.join().unwrap();
+    }
+
+    fn greet(req: &Request, res: Response) -> ServerResponse {
+        let config = req.state::<Config>().ok_or("Config is not registered")?;
+        let visits = req.state::<Visits>().ok_or("Visits is not registered")?;
+
+        let count = visits.0.fetch_add(1, Ordering::SeqCst) + 1;
+        let body = format!(
+            "{} {} #{}",
+            config.greeting,
+            req.param("name").unwrap_or_default(),
+            count
+        );
+
+        res.body(body.into_bytes()).into()
+    }
+
+    fn missing(req: &Request, res: Response) -> ServerResponse {
+        match req.state::<Cache>() {
+            Some(_) => res.status(Status::Ok).into(),
+            None => res.status(Status::NotFound).into(),
+        }
+    }
+}
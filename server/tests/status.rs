@@ -0,0 +1,86 @@
+mod test_utils;
+
+#[cfg(test)]
+mod tests {
+
+    use std::{
+        io::{Read, Write},
+        net::TcpStream,
+        sync::Arc,
+        thread,
+    };
+
+    use server::{app::App, models::status::Status};
+
+    use crate::test_utils::{local_listener, wait_until_server_ready};
+
+    #[test]
+    fn codes_round_trip() {
+        for code in [100, 204, 301, 308, 409, 413, 422, 431, 503, 505] {
+            let status = Status::from_u16(code).unwrap();
+            assert_eq!(status.as_u16(), code);
+        }
+
+        assert_eq!(Status::from_u16(299), None);
+        assert_eq!(Status::ContentTooLarge.to_string(), "413 Content Too Large");
+        assert_eq!(
+            Status::custom(599, "Upstream Melted").unwrap().to_string(),
+            "599 Upstream Melted"
+        );
+    }
+
+    #[test]
+    fn custom_statuses_are_checked() {
+        assert!(Status::custom(100, "").is_some());
+        assert!(Status::custom(999, "Tab\tis fine").is_some());
+        assert_eq!(Status::custom(7, "x"), None);
+        assert_eq!(Status::custom(1000, "x"), None);
+        assert_eq!(Status::custom(599, "x\r\nEvil: 1"), None);
+        assert_eq!(Status::custom(599, "x\nEvil: 1"), None);
+        assert_eq!(Status::custom(200, "Fine"), Some(Status::Ok));
+    }
+
+    #[test]
+    fn categories() {
+        assert!(Status::Continue.is_informational());
+        assert!(Status::NoContent.is_success());
+        assert!(Status::SeeOther.is_redirection());
+        assert!(Status::Conflict.is_client_error());
+        assert!(Status::ServiceUnavailable.is_server_error());
+        assert!(Status::custom(299, "Fine").unwrap().is_success());
+        assert!(!Status::Forbidden.is_server_error());
+    }
+
+    #[test]
+    fn custom_status_is_sent() {
+        let app = App::from_listener(local_listener())
+            .unwrap()
+            .get("teapot", || Status::custom(418, "I'm a teapot").unwrap())
+            .get("gone", || Status::Gone)
+            .build()
+            .unwrap();
+        let addr = app.local_addr().unwrap().to_string();
+
+        let server = Arc::clone(&app);
+        let handle = thread::spawn(move || server.run());
+
+        wait_until_server_ready(&addr);
+
+        for (path, status_line) in [
+            ("/teapot", "HTTP/1.1 418 I'm a teapot"),
+            ("/gone", "HTTP/1.1 410 Gone"),
+        ] {
+            let mut stream = TcpStream::connect(&addr).unwrap();
+            let request = format!("GET {} HTTP/1.1\r\nConnection: close\r\n\r\n", path);
+            stream.write_all(request.as_bytes()).unwrap();
+
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+
+            assert_eq!(response.lines().next(), Some(status_line));
+        }
+
+        app.shutdown();
+        handle.join().unwrap();
+    }
+}
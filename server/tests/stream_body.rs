@@ -0,0 +1,121 @@
+mod test_utils;
+
+#[cfg(test)]
+mod tests {
+
+    use std::{
+        fs,
+        io::{self, Read, Write},
+        net::TcpStream,
+        sync::Arc,
+        thread,
+    };
+
+    use reqwest::blocking::{Body, Client};
+    use server::{
+        app::{App, Route, ServerResponse, limits::Limits},
+        models::{request::Request, response::Response, status::Status},
+        router::Router,
+    };
+
+    use crate::test_utils::{local_listener, wait_until_server_ready};
+
+    const LIMIT: u64 = 64 * 1024;
+
+    fn upload_data(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i % 251) as u8).collect()
+    }
+
+    #[test]
+    fn bodies_are_streamed_to_handlers() {
+        let uploads = Router::new("uploads").post(":name", Route::new(upload).stream_body());
+        let app = App::builder()
+            .limits(Limits {
+                max_body_size: LIMIT,
+                ..Limits::default()
+            })
+            .listener(local_listener())
+            .unwrap()
+            .with_router(uploads)
+            .post("buffered", buffered)
+            .post("ignore", Route::new(ignore).stream_body())
+            .build()
+            .unwrap();
+        let addr = app.local_addr().unwrap().to_string();
+
+        let server = Arc::clone(&app);
+        let handle = thread::spawn(move || server.run());
+
+        wait_until_server_ready(&addr);
+
+        let client = Client::new();
+        let post = |path: &str, body: Body| {
+            client
+                .post(format!("http://{}/{}", addr, path))
+                .body(body)
+                .send()
+                .expect("Couldn't send request to the server")
+        };
+
+        // Sized and chunked uploads both land on disk untouched.
+        let data = upload_data(50_000);
+        let res = post("uploads/sized", Body::from(data.clone()));
+        assert_eq!(res.status(), 201);
+        assert_eq!(res.text().unwrap(), "50000");
+        assert_eq!(fs::read(upload_path("sized")).unwrap(), data);
+
+        let res = post("uploads/chunked", Body::new(io::Cursor::new(data.clone())));
+        assert_eq!(res.status(), 201);
+        assert_eq!(fs::read(upload_path("chunked")).unwrap(), data);
+
+        // The limit holds for streamed bodies, whether announced up front or
+        // only found out while reading.
+        let big = upload_data(LIMIT as usize + 1);
+        assert_eq!(post("uploads/big", Body::from(big.clone())).status(), 413);
+        assert_eq!(
+            post("uploads/big", Body::new(io::Cursor::new(big.clone()))).status(),
+            413
+        );
+        assert_eq!(post("buffered", Body::from(big)).status(), 413);
+        assert_eq!(post("buffered", Body::from(data)).status(), 200);
+
+        // A handler that leaves the body unread still gets its response out,
+        // and the connection is closed instead of reused.
+        let mut stream = TcpStream::connect(&addr).unwrap();
+        stream
+            .write_all(b"POST /ignore HTTP/1.1\r\nContent-Length: 5\r\n\r\nhello")
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 204"));
+        assert!(response.to_lowercase().contains("connection: close"));
+
+        app.shutdown();
+        handle.join().unwrap();
+    }
+
+    fn upload_path(name: &str) -> String {
+        format!("/tmp/stream-body-{}", name)
+    }
+
+    fn upload(req: &Request, res: Response) -> ServerResponse {
+        let mut body = req.body_reader().ok_or("Body is not streamed")?;
+        let mut file = fs::File::create(upload_path(req.param("name").unwrap_or_default()))?;
+
+        let written = io::copy(&mut *body, &mut file)?;
+
+        res.status(Status::Created)
+            .body(written.to_string().into_bytes())
+            .into()
+    }
+
+    fn buffered(req: &Request, res: Response) -> ServerResponse {
+        assert!(req.body_reader().is_none());
+
+        res.body(req.body.len().to_string().into_bytes()).into()
+    }
+
+    fn ignore(_req: &Request, res: Response) -> ServerResponse {
+        res.status(Status::NoContent).into()
+    }
+}
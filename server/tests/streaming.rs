@@ -0,0 +1,157 @@
+mod test_utils;
+
+#[cfg(test)]
+mod tests {
+
+    use std::{
+        io::{Cursor, Read, Write},
+        net::TcpStream,
+        sync::{
+            Arc, Mutex,
+            mpsc::{self, Receiver},
+        },
+        thread,
+        time::Duration,
+    };
+
+    use reqwest::blocking::Client;
+    use server::{
+        app::{App, ServerResponse},
+        models::{request::Request, response::Response},
+    };
+
+    use crate::test_utils::{local_listener, wait_until_server_ready};
+
+    #[test]
+    fn bodies_are_streamed() {
+        let app = App::from_listener(local_listener())
+            .unwrap()
+            .get("chunks", chunks)
+            .get("sized", sized)
+            .get("export", export)
+            .build()
+            .unwrap();
+        let addr = app.local_addr().unwrap().to_string();
+
+        let server = Arc::clone(&app);
+        let handle = thread::spawn(move || server.run());
+
+        wait_until_server_ready(&addr);
+
+        let client = Client::new();
+        let get = |path: &str| {
+            client
+                .get(format!("http://{}/{}", addr, path))
+                .send()
+                .expect("Couldn't send request to the server")
+        };
+
+        let res = get("chunks");
+        assert_eq!(res.headers()["transfer-encoding"], "chunked");
+        assert!(res.headers().get("content-length").is_none());
+        assert_eq!(res.text().unwrap(), "one,two,three");
+
+        let res = get("sized");
+        assert_eq!(res.headers()["content-length"], "5");
+        assert!(res.headers().get("transfer-encoding").is_none());
+        assert_eq!(res.text().unwrap(), "sized");
+
+        let res = get("export");
+        assert_eq!(res.headers()["transfer-encoding"], "chunked");
+        assert_eq!(res.bytes().unwrap().as_ref(), export_data().as_slice());
+
+        // The connection is still usable after a chunked response.
+        assert_eq!(get("sized").text().unwrap(), "sized");
+
+        // HTTP/1.0 clients get the raw bytes, delimited by closing.
+        let mut stream = TcpStream::connect(&addr).unwrap();
+        stream.write_all(b"GET /chunks HTTP/1.0\r\n\r\n").unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(!response.to_lowercase().contains("transfer-encoding"));
+        assert!(response.ends_with("\r\n\r\none,two,three"));
+
+        app.shutdown();
+        handle.join().unwrap();
+    }
+
+    struct Feed(Mutex<Option<Receiver<&'static str>>>);
+
+    #[test]
+    fn chunks_are_sent_as_they_are_produced() {
+        let (feed, rx) = mpsc::channel();
+
+        let app = App::from_listener(local_listener())
+            .unwrap()
+            .with_state(Feed(Mutex::new(Some(rx))))
+            .get("live", live)
+            .build()
+            .unwrap();
+        let addr = app.local_addr().unwrap().to_string();
+
+        let server = Arc::clone(&app);
+        let handle = thread::spawn(move || server.run());
+
+        wait_until_server_ready(&addr);
+
+        let mut stream = TcpStream::connect(&addr).unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        stream
+            .write_all(b"GET /live HTTP/1.1\r\nConnection: close\r\n\r\n")
+            .unwrap();
+
+        // The head and the first chunk arrive while the body is still being
+        // produced; the rest can't exist until the feed is closed.
+        feed.send("first").unwrap();
+        let mut received = Vec::new();
+        let mut buf = [0; 1024];
+        while !String::from_utf8_lossy(&received).contains("first") {
+            let read = stream.read(&mut buf).expect("First chunk wasn't sent");
+            assert_ne!(read, 0, "Connection closed early");
+            received.extend_from_slice(&buf[..read]);
+        }
+        assert!(received.starts_with(b"HTTP/1.1 200 OK"));
+
+        feed.send("second").unwrap();
+        drop(feed);
+        stream.read_to_end(&mut received).unwrap();
+        assert!(
+            String::from_utf8_lossy(&received).ends_with("5\r\nfirst\r\n6\r\nsecond\r\n0\r\n\r\n")
+        );
+
+        app.shutdown();
+        handle.join().unwrap();
+    }
+
+    fn live(req: &Request, res: Response) -> ServerResponse {
+        let feed = req
+            .state::<Feed>()
+            .and_then(|feed| feed.0.lock().unwrap().take())
+            .ok_or("Feed was already taken")?;
+
+        res.body_chunks(feed.into_iter().map(|part| part.as_bytes().to_vec()))
+            .into()
+    }
+
+    fn export_data() -> Vec<u8> {
+        (0..100_000u32).map(|i| (i % 251) as u8).collect()
+    }
+
+    fn chunks(_req: &Request, res: Response) -> ServerResponse {
+        let parts = ["one", ",", "", "two", ",three"];
+
+        res.body_chunks(parts.into_iter().map(|p| p.as_bytes().to_vec()))
+            .into()
+    }
+
+    fn sized(_req: &Request, res: Response) -> ServerResponse {
+        res.body_reader(Cursor::new(b"sized".to_vec()), Some(5))
+            .into()
+    }
+
+    fn export(_req: &Request, res: Response) -> ServerResponse {
+        res.body_reader(Cursor::new(export_data()), None).into()
+    }
+}
@@ -1,4 +1,7 @@
-use std::net::TcpStream;
+#![allow(dead_code)]
+
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
 use std::time::Duration;
 
 pub const BASE_URL: &str = "127.0.0.1:4221";
@@ -13,3 +16,28 @@ pub fn wait_until_server_ready(addr: &str) {
 
     panic!("Server did not become ready in time");
 }
+
+// Binds an ephemeral port to hand to `App::from_listener`, so tests in the
+// same binary can run their servers in parallel without racing for ports.
+pub fn local_listener() -> TcpListener {
+    TcpListener::bind("127.0.0.1:0").expect("Couldn't bind a local port")
+}
+
+// Writes raw bytes to a fresh connection and reads until the server closes
+// it, for requests a real client wouldn't send.
+pub fn send(addr: &str, raw: &[u8]) -> String {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    stream.write_all(raw).unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+
+    response
+}
+
+pub fn get(addr: &str, path: &str) -> (u16, String) {
+    let res = reqwest::blocking::get(format!("http://{}{}", addr, path))
+        .expect("Couldn't send request to the server");
+
+    (res.status().as_u16(), res.text().unwrap())
+}
@@ -0,0 +1,57 @@
+#[cfg(test)]
+mod tests {
+
+    use std::{collections::HashSet, sync::mpsc, thread};
+
+    use server::thread_pool::{PoolCreationError, ThreadPool};
+
+    #[test]
+    fn empty_pools_are_rejected() {
+        assert!(matches!(
+            ThreadPool::build(0),
+            Err(PoolCreationError::ZeroSize)
+        ));
+    }
+
+    #[test]
+    fn workers_are_named() {
+        let pool = ThreadPool::builder(3)
+            .name("test-pool")
+            .stack_size(256 * 1024)
+            .build()
+            .unwrap();
+        assert_eq!(pool.size(), 3);
+
+        let (sender, receiver) = mpsc::channel();
+        for _ in 0..30 {
+            let sender = sender.clone();
+            pool.execute(move || {
+                let name = thread::current().name().map(ToString::to_string);
+                sender.send(name).unwrap();
+            });
+        }
+        drop(sender);
+
+        let names = receiver.iter().take(30).flatten().collect::<HashSet<_>>();
+        assert!(!names.is_empty());
+        assert!(names.iter().all(|n| n.starts_with("test-pool-")));
+    }
+
+    #[test]
+    fn panicking_jobs_leave_workers_running() {
+        let pool = ThreadPool::build(1).unwrap();
+
+        let (sender, receiver) = mpsc::channel();
+        for i in 0..3 {
+            let sender = sender.clone();
+            pool.execute(move || {
+                if i < 2 {
+                    panic!("job {} failed", i);
+                }
+                sender.send(i).unwrap();
+            });
+        }
+
+        assert_eq!(receiver.recv().unwrap(), 2);
+    }
+}
@@ -0,0 +1,148 @@
+mod test_utils;
+
+#[cfg(test)]
+mod tests {
+
+    use std::{
+        io::{self, Read, Write},
+        net::TcpStream,
+        sync::Arc,
+        thread,
+        time::{Duration, Instant},
+    };
+
+    use server::{
+        app::{App, Route, ServerResponse, timeouts::Timeouts},
+        models::{request::Request, response::Response},
+    };
+
+    use crate::test_utils::{local_listener, wait_until_server_ready};
+
+    // Sends `parts` with `pause` in between, then waits for the response.
+    fn trickle(addr: &str, parts: &[&str], pause: Duration) -> (String, Duration) {
+        let started = Instant::now();
+        let mut stream = TcpStream::connect(addr).unwrap();
+
+        for part in parts {
+            stream.write_all(part.as_bytes()).unwrap();
+            thread::sleep(pause);
+        }
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        (response, started.elapsed())
+    }
+
+    #[test]
+    fn slow_clients_time_out() {
+        let app = App::builder()
+            .timeouts(Timeouts {
+                header_read: Duration::from_millis(600),
+                body_read: Duration::from_millis(800),
+                write: Duration::from_secs(1),
+                request: Duration::from_millis(1000),
+            })
+            .listener(local_listener())
+            .unwrap()
+            .post("echo", echo)
+            .post("upload", Route::new(upload).stream_body())
+            .build()
+            .unwrap();
+        let addr = app.local_addr().unwrap().to_string();
+
+        let server = Arc::clone(&app);
+        let handle = thread::spawn(move || server.run());
+
+        wait_until_server_ready(&addr);
+
+        // Trickling header bytes doesn't keep the head open past its timeout.
+        let (response, elapsed) = trickle(
+            &addr,
+            &["POST /echo HTTP/1.1\r\n", "X-Slow", ": ", "a", "b"],
+            Duration::from_millis(100),
+        );
+        assert!(response.starts_with("HTTP/1.1 408 Request Timeout"));
+        assert!(elapsed < Duration::from_millis(1000), "{:?}", elapsed);
+
+        // A body that stops arriving.
+        let (response, _) = trickle(
+            &addr,
+            &["POST /echo HTTP/1.1\r\nContent-Length: 10\r\n\r\nabc"],
+            Duration::ZERO,
+        );
+        assert!(response.starts_with("HTTP/1.1 408 Request Timeout"));
+
+        // A body that keeps arriving, but too slowly to finish in time.
+        let parts = [
+            "POST /echo HTTP/1.1\r\nContent-Length: 100\r\n\r\n",
+            "a",
+            "b",
+            "c",
+            "d",
+        ];
+        let (response, elapsed) = trickle(&addr, &parts, Duration::from_millis(200));
+        assert!(response.starts_with("HTTP/1.1 408 Request Timeout"));
+        assert!(elapsed < Duration::from_millis(1500), "{:?}", elapsed);
+
+        // Streaming handlers see the timeout as a read error and the client
+        // still gets a 408.
+        let (response, _) = trickle(
+            &addr,
+            &["POST /upload HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n3\r\nabc\r\n"],
+            Duration::ZERO,
+        );
+        assert!(response.starts_with("HTTP/1.1 408 Request Timeout"));
+
+        app.shutdown();
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn zero_turns_timeouts_off() {
+        let app = App::builder()
+            .keep_alive(Duration::ZERO)
+            .timeouts(Timeouts {
+                header_read: Duration::ZERO,
+                body_read: Duration::ZERO,
+                write: Duration::ZERO,
+                request: Duration::ZERO,
+            })
+            .listener(local_listener())
+            .unwrap()
+            .post("echo", echo)
+            .build()
+            .unwrap();
+        let addr = app.local_addr().unwrap().to_string();
+
+        let server = Arc::clone(&app);
+        let handle = thread::spawn(move || server.run());
+
+        wait_until_server_ready(&addr);
+
+        // Requests are served without waiting on any clock, and with no
+        // keep-alive the connection is closed after the response.
+        let (response, _) = trickle(
+            &addr,
+            &["POST /echo HTTP/1.1\r\nContent-Length: 5\r\n\r\n", "hello"],
+            Duration::from_millis(100),
+        );
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+        assert!(response.to_lowercase().contains("connection: close"));
+        assert!(response.ends_with("hello"));
+
+        app.shutdown();
+        handle.join().unwrap();
+    }
+
+    fn echo(req: &Request, res: Response) -> ServerResponse {
+        res.body(req.body.to_vec()).into()
+    }
+
+    fn upload(req: &Request, res: Response) -> ServerResponse {
+        let mut body = req.body_reader().ok_or("Body is not streamed")?;
+        io::copy(&mut *body, &mut io::sink())?;
+
+        res.into()
+    }
+}
@@ -17,7 +17,8 @@ mod tests {
     fn user_agent() {
         let app = App::new(BASE_URL)
             .get("usr-agent", user_agent_handler)
-            .build();
+            .build()
+            .unwrap();
 
         let server = Arc::clone(&app);
         let handle = thread::spawn(move || server.run());
@@ -53,7 +54,7 @@ mod tests {
     }
 
     fn user_agent_handler(req: &Request, res: Response) -> ServerResponse {
-        res.body(req.user_agent.clone().into_bytes())
+        res.body(req.user_agent().as_bytes().to_vec())
             .status(Status::Ok)
             .into()
     }
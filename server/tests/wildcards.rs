@@ -0,0 +1,77 @@
+mod test_utils;
+
+#[cfg(test)]
+mod tests {
+
+    use std::{sync::Arc, thread};
+
+    use server::{
+        app::{App, ServerResponse},
+        models::{request::Request, response::Response},
+        router::Router,
+    };
+
+    use crate::test_utils::{get, local_listener, wait_until_server_ready};
+
+    #[test]
+    fn catch_all_serves_subtree() {
+        let assets = Router::new("static")
+            .get("*path", path_handler)
+            .get("index", index_handler);
+        let app = App::from_listener(local_listener())
+            .unwrap()
+            .with_router(Router::new("v1").route(assets))
+            .build()
+            .unwrap();
+        let addr = app.local_addr().unwrap().to_string();
+
+        let server = Arc::clone(&app);
+        let handle = thread::spawn(move || server.run());
+
+        wait_until_server_ready(&addr);
+
+        assert_eq!(
+            get(&addr, "/v1/static/css/site.css"),
+            (200, String::from("css/site.css"))
+        );
+        assert_eq!(
+            get(&addr, "/v1/static/logo.png"),
+            (200, String::from("logo.png"))
+        );
+        assert_eq!(get(&addr, "/v1/static/index"), (200, String::from("index")));
+        assert_eq!(get(&addr, "/v1/static").0, 404);
+
+        app.shutdown();
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn wildcard_matches_single_segment() {
+        let app = App::from_listener(local_listener())
+            .unwrap()
+            .get("*/index", index_handler)
+            .build()
+            .unwrap();
+        let addr = app.local_addr().unwrap().to_string();
+
+        let server = Arc::clone(&app);
+        let handle = thread::spawn(move || server.run());
+
+        wait_until_server_ready(&addr);
+
+        assert_eq!(get(&addr, "/docs/index"), (200, String::from("index")));
+        assert_eq!(get(&addr, "/docs/api/index").0, 404);
+
+        app.shutdown();
+        handle.join().unwrap();
+    }
+
+    fn path_handler(req: &Request, res: Response) -> ServerResponse {
+        res.body(req.param("path").unwrap_or_default().as_bytes().to_vec())
+            .into()
+    }
+
+    fn index_handler(_: &Request, res: Response) -> ServerResponse {
+        res.body(b"index".to_vec()).into()
+    }
+}